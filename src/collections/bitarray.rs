@@ -31,6 +31,10 @@ impl BitArray {
         self.len
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     /// # Description 
     /// returns number of bits available
     pub fn available_bits(&self) -> u128 {
@@ -63,6 +67,12 @@ impl BitArray {
     }
 }
 
+impl Default for BitArray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl std::fmt::Display for BitArray {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[")?;
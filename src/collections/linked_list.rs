@@ -1,4 +1,4 @@
-use std::ops::*;
+use std::{cmp::Ordering, convert::TryInto, fmt, iter::FromIterator, marker::PhantomData, ops::*};
 
 /// # Description
 /// A LinkedList with option Nodes
@@ -36,7 +36,7 @@ where
 {
     /// In this implementation 'memory' is just a vector.  This module implements what is referred to as a\
     /// 'vector-backed' linked list.
-    fn get_memory(&mut self) -> &Vec<NodeType>;
+    fn get_memory(&self) -> &Vec<NodeType>;
     /// Returns a pointer to the pool
     fn get_pool(&self) -> u32;
     /// Returns a pointer to the rear dll
@@ -46,25 +46,59 @@ where
     /// returns the length of the dll
     fn len(&self) -> usize;
 
+    /// returns true when the dll holds no nodes
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// returns the number of freed nodes waiting in the pool to be reused \
+    /// every slot in memory is either linked into the list or sitting in the pool
+    fn pool_len(&self) -> usize {
+        self.get_memory().len() - self.len()
+    }
+
     /// returns a mutable pointer to memory \
     /// Even though this is considered safe in rust, I would prefren manual manipulation \
     /// to be done by code in this module \
+    /// # Safety
+    /// Callers must keep the node links, `front`, `rear` and `pool` consistent
     unsafe fn get_memory_mut(&mut self) -> &mut Vec<NodeType>;
 
     /// returns a mutable refrence to the pool pointer for external manipulation
+    /// # Safety
+    /// The pool must remain a chain of freed nodes terminated by `!0`
     unsafe fn get_pool_mut(&mut self) -> &mut u32;
 
+    /// overwrites the front and rear pointers, used by operations that relink the whole list
+    /// # Safety
+    /// `front` and `rear` must be the ends of the circle formed by the node links
+    unsafe fn set_ends(&mut self, front: u32, rear: u32);
+
     /// inserts a node to the left or right of location `cur_node` in "memory" \
     /// `dir` =  0  when inserting to the left of cur_node \
     /// `dir` =  1  when inserting to the right of cur_node
     fn insert(&mut self, cur_node: u32, dir: usize, data: DataType);
 
-    /// removes a node at location `cur_node` in "memmory"
+    /// removes a node at location `cur_node` in "memmory" \
+    /// This is the unchecked fast path, `cur_node` has to be linked. Use `try_remove` for pointers
+    /// you don't trust
     fn remove(&mut self, cur_node: u32) -> Option<DataType>;
 
+    /// checked version of `remove`, says why `ptr` couldn't be removed instead of corrupting the links
+    fn try_remove(&mut self, ptr: u32) -> Result<DataType, RemoveError> {
+        if ptr as usize >= self.get_memory().len() {
+            Err(RemoveError::OutOfBounds)
+        } else if !self.is_linked(ptr) {
+            Err(RemoveError::NotLinked)
+        } else {
+            self.remove(ptr).ok_or(RemoveError::NotLinked)
+        }
+    }
+
     /// allocates a new node
     fn allocate(&mut self, data: DataType) -> u32;
 
+    /// pushes `data` onto the front of the list, see `push_rear` for the other end
     fn push_front(&mut self, data: DataType) {
         self.insert(self.get_front(), 0, data);
     }
@@ -81,6 +115,450 @@ where
         self.remove(self.get_rear())
     }
 
+    /// returns the data at the front of the list, `None` when the list is empty
+    fn peek_front<'a>(&'a self) -> Option<&'a DataType>
+    where
+        NodeType: 'a,
+    {
+        if self.is_empty() {
+            None
+        } else {
+            self.get_memory()[self.get_front() as usize].get_data()
+        }
+    }
+
+    fn peek_front_mut<'a>(&'a mut self) -> Option<&'a mut DataType>
+    where
+        NodeType: 'a,
+    {
+        if self.is_empty() {
+            None
+        } else {
+            let front = self.get_front() as usize;
+            // only the data is touched, the links stay as they are
+            unsafe { self.get_memory_mut()[front].get_data_mut() }
+        }
+    }
+
+    /// returns the data at the rear of the list, `None` when the list is empty
+    fn peek_rear<'a>(&'a self) -> Option<&'a DataType>
+    where
+        NodeType: 'a,
+    {
+        if self.is_empty() {
+            None
+        } else {
+            self.get_memory()[self.get_rear() as usize].get_data()
+        }
+    }
+
+    fn peek_rear_mut<'a>(&'a mut self) -> Option<&'a mut DataType>
+    where
+        NodeType: 'a,
+    {
+        if self.is_empty() {
+            None
+        } else {
+            let rear = self.get_rear() as usize;
+            // only the data is touched, the links stay as they are
+            unsafe { self.get_memory_mut()[rear].get_data_mut() }
+        }
+    }
+
+    /// returns true when `ptr` points at a node that is currently linked into the list \
+    /// freed nodes always have a null next pointer, so stale pointers are caught here too
+    fn is_linked(&self, ptr: u32) -> bool {
+        !self.is_empty()
+            && self
+                .get_memory()
+                .get(ptr as usize)
+                .is_some_and(|node| node.get_children()[1] != !0)
+    }
+
+    /// inserts `data` right after the node at `ptr` and returns the pointer of the new node
+    /// # Panics
+    /// when `ptr` isn't linked into the list (out of bounds or already removed)
+    #[track_caller]
+    fn insert_after(&mut self, ptr: u32, data: DataType) -> u32 {
+        assert!(self.is_linked(ptr), "insert_after: node {} is not in the list", ptr);
+        self.insert(ptr, 1, data);
+        self.get_memory()[ptr as usize].get_children()[1]
+    }
+
+    /// inserts `data` right before the node at `ptr` and returns the pointer of the new node
+    /// # Panics
+    /// when `ptr` isn't linked into the list (out of bounds or already removed)
+    #[track_caller]
+    fn insert_before(&mut self, ptr: u32, data: DataType) -> u32 {
+        assert!(self.is_linked(ptr), "insert_before: node {} is not in the list", ptr);
+        self.insert(ptr, 0, data);
+        self.get_memory()[ptr as usize].get_children()[0]
+    }
+
+    /// splits the list in two at `ptr`, everything from `ptr` up to the rear is moved into the
+    /// returned list and `self` ends just before `ptr`
+    /// # Comments
+    /// Nodes can't be shared between two memory vectors so the suffix is moved node by node into the
+    /// new list's storage. This is O(k) in the length of the moved suffix, payloads are moved, not cloned.
+    /// The freed slots in `self` go to its pool.
+    /// # Panics
+    /// when `ptr` isn't linked into the list
+    #[track_caller]
+    fn split_off(&mut self, ptr: u32) -> Self
+    where
+        Self: Default + Sized,
+    {
+        assert!(self.is_linked(ptr), "split_off: node {} is not in the list", ptr);
+        let mut tail = Self::default();
+        loop {
+            let rear = self.get_rear();
+            if let Some(data) = self.remove(rear) {
+                tail.push_front(data);
+            }
+            if rear == ptr {
+                break tail;
+            }
+        }
+    }
+
+    /// removes every item front to rear, yielding them by value \
+    /// The nodes go back to the pool so refilling the list doesn't allocate. Dropping the iterator
+    /// early still empties the list, like `Vec::drain`
+    fn drain(&mut self) -> DLLDrain<'_, Self, NodeType, DataType>
+    where
+        Self: Sized,
+    {
+        DLLDrain {
+            list: self,
+            _node: PhantomData,
+        }
+    }
+
+    /// keeps only the items for which `f` returns true, walking once from the front \
+    /// removed nodes go back to the pool
+    fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&DataType) -> bool,
+    {
+        self.retain_mut(|data| f(data))
+    }
+
+    /// same as `retain` but `f` gets to mutate the items it keeps
+    fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut DataType) -> bool,
+    {
+        let mut node = self.get_front();
+        for _ in 0..self.len() {
+            // only data is touched through this reference, links are updated by `remove`
+            let current = unsafe { &mut self.get_memory_mut()[node as usize] };
+            let next = current.get_children()[1];
+            if !current.get_data_mut().map_or(true, &mut f) {
+                self.remove(node);
+            }
+            node = next;
+        }
+    }
+
+    /// removes consecutive equal items keeping the first of each run, see `dedup_by`
+    fn dedup(&mut self)
+    where
+        DataType: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// removes consecutive items for which `same` returns true in a single pass, the later node
+    /// of each pair goes back to the pool \
+    /// Like `Vec::dedup_by`, `same` gets the item being looked at and then the item it would be a
+    /// duplicate of
+    fn dedup_by<F>(&mut self, mut same: F)
+    where
+        F: FnMut(&DataType, &DataType) -> bool,
+    {
+        let mut kept = self.get_front();
+        for _ in 1..self.len() {
+            let memory = self.get_memory();
+            let node = memory[kept as usize].get_children()[1];
+            let is_dup = match (memory[node as usize].get_data(), memory[kept as usize].get_data()) {
+                (Some(data), Some(kept_data)) => same(data, kept_data),
+                _ => false,
+            };
+            if is_dup {
+                self.remove(node);
+            } else {
+                kept = node;
+            }
+        }
+    }
+
+    /// returns a pointer to the first node from the front whose data satisfies `pred`
+    fn find<F>(&self, mut pred: F) -> Option<u32>
+    where
+        F: FnMut(&DataType) -> bool,
+    {
+        let front = self.get_front();
+        if !self.is_linked(front) {
+            None
+        } else if self.get_memory()[front as usize].get_data().is_some_and(&mut pred) {
+            Some(front)
+        } else {
+            self.find_from(front, pred)
+        }
+    }
+
+    /// same as `find` but only searches the nodes after `start`, so passing the previous match
+    /// continues the search \
+    /// Stops at the rear (no wrap-around) and returns `None` when `start` isn't in the list
+    fn find_from<F>(&self, start: u32, mut pred: F) -> Option<u32>
+    where
+        F: FnMut(&DataType) -> bool,
+    {
+        if !self.is_linked(start) {
+            return None;
+        }
+        let memory = self.get_memory();
+        let mut node = start;
+        while node != self.get_rear() {
+            node = memory[node as usize].get_children()[1];
+            if memory[node as usize].get_data().is_some_and(&mut pred) {
+                return Some(node);
+            }
+        }
+        None
+    }
+
+    /// sorts the list with `DataType`'s ordering, see `sort_by`
+    fn sort(&mut self)
+    where
+        DataType: Ord,
+    {
+        self.sort_by(Ord::cmp)
+    }
+
+    /// stable sort using `cmp`
+    /// # Comments
+    /// Bottom-up merge sort over the next pointers, O(n log n) time and O(1) extra space.
+    /// Only links are rewritten so payloads never move and node pointers keep referring to the same items.
+    fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&DataType, &DataType) -> Ordering,
+    {
+        if self.len() < 2 {
+            return;
+        }
+        let (front, rear) = (self.get_front(), self.get_rear());
+        // links are rebuilt below, data is only read
+        let memory = unsafe { self.get_memory_mut() };
+        let next = |memory: &Vec<NodeType>, node: u32| memory[node as usize].get_children()[1];
+
+        // treat the list as a null terminated singly linked list while merging
+        memory[rear as usize].get_children_mut()[1] = !0;
+        let mut head = front;
+        let mut tail;
+        let mut run = 1;
+        loop {
+            let mut p = head;
+            head = !0;
+            tail = !0;
+            let mut merges = 0;
+            while p != !0 {
+                merges += 1;
+                let mut q = p;
+                let mut p_len = 0;
+                while p_len < run && q != !0 {
+                    p_len += 1;
+                    q = next(memory, q);
+                }
+                let mut q_len = run;
+                while p_len > 0 || (q_len > 0 && q != !0) {
+                    // ties go to the left run which keeps the sort stable
+                    let take_p = q_len == 0
+                        || q == !0
+                        || (p_len > 0
+                            && cmp(
+                                memory[p as usize].get_data().unwrap(),
+                                memory[q as usize].get_data().unwrap(),
+                            ) != Ordering::Greater);
+                    let node = if take_p {
+                        let node = p;
+                        p = next(memory, p);
+                        p_len -= 1;
+                        node
+                    } else {
+                        let node = q;
+                        q = next(memory, q);
+                        q_len -= 1;
+                        node
+                    };
+                    if tail == !0 {
+                        head = node;
+                    } else {
+                        memory[tail as usize].get_children_mut()[1] = node;
+                    }
+                    tail = node;
+                }
+                p = q;
+            }
+            memory[tail as usize].get_children_mut()[1] = !0;
+            if merges <= 1 {
+                break;
+            }
+            run *= 2;
+        }
+
+        // restore the prev pointers and close the circle again
+        let mut prev = tail;
+        let mut node = head;
+        while node != !0 {
+            memory[node as usize].get_children_mut()[0] = prev;
+            prev = node;
+            node = next(memory, node);
+        }
+        memory[tail as usize].get_children_mut()[1] = head;
+        unsafe {
+            self.set_ends(head, tail);
+        }
+    }
+
+    /// reverses the list in place by swapping every node's prev/next pointers, payloads don't move
+    fn reverse(&mut self) {
+        let (front, rear) = (self.get_front(), self.get_rear());
+        let len = self.len();
+        unsafe {
+            let memory = self.get_memory_mut();
+            let mut node = front;
+            for _ in 0..len {
+                let children = memory[node as usize].get_children_mut();
+                children.swap(0, 1);
+                // the old next pointer now sits in slot 0
+                node = children[0];
+            }
+            self.set_ends(rear, front);
+        }
+    }
+
+    /// exchanges the positions of the nodes at `a` and `b`
+    /// # Comments
+    /// The nodes are relinked, the data stays put. So pointers held outside the list keep
+    /// referring to the same items, only their positions change.
+    /// # Panics
+    /// when either pointer isn't linked into the list
+    #[track_caller]
+    fn swap(&mut self, a: u32, b: u32) {
+        assert!(
+            self.is_linked(a) && self.is_linked(b),
+            "swap: node {} or {} is not in the list",
+            a,
+            b
+        );
+        if a == b {
+            return;
+        }
+        let (front, rear) = (self.get_front(), self.get_rear());
+        let len = self.len();
+        let memory = unsafe { self.get_memory_mut() };
+        let link = |memory: &mut Vec<NodeType>, from: u32, to: u32| {
+            memory[from as usize].get_children_mut()[1] = to;
+            memory[to as usize].get_children_mut()[0] = from;
+        };
+        let [a_prev, a_next] = *memory[a as usize].get_children();
+        let [b_prev, b_next] = *memory[b as usize].get_children();
+
+        if len == 2 {
+            // a and b are each other's prev and next, only the ends change
+        } else if a_next == b {
+            link(memory, a_prev, b);
+            link(memory, b, a);
+            link(memory, a, b_next);
+        } else if b_next == a {
+            link(memory, b_prev, a);
+            link(memory, a, b);
+            link(memory, b, a_next);
+        } else {
+            link(memory, a_prev, b);
+            link(memory, b, a_next);
+            link(memory, b_prev, a);
+            link(memory, a, b_next);
+        }
+
+        let other = |node: u32| match node {
+            n if n == a => b,
+            n if n == b => a,
+            n => n,
+        };
+        unsafe {
+            self.set_ends(other(front), other(rear));
+        }
+    }
+
+    /// returns the pointer of the node at logical position `index` (0 is the front) \
+    /// walks from whichever end is closer so this is O(min(index, len - index))
+    fn ptr_at(&self, index: usize) -> Option<u32> {
+        let len = self.len();
+        if index >= len {
+            return None;
+        }
+        let memory = self.get_memory();
+        let (mut node, dir, steps) = if index < len / 2 {
+            (self.get_front(), 1, index)
+        } else {
+            (self.get_rear(), 0, len - 1 - index)
+        };
+        for _ in 0..steps {
+            node = memory[node as usize].get_children()[dir];
+        }
+        Some(node)
+    }
+
+    /// returns the data at logical position `index`, `None` when out of range
+    fn get<'a>(&'a self, index: usize) -> Option<&'a DataType>
+    where
+        NodeType: 'a,
+    {
+        let node = self.ptr_at(index)?;
+        self.get_memory()[node as usize].get_data()
+    }
+
+    fn get_mut<'a>(&'a mut self, index: usize) -> Option<&'a mut DataType>
+    where
+        NodeType: 'a,
+    {
+        let node = self.ptr_at(index)?;
+        // only the data is touched, the links stay as they are
+        unsafe { self.get_memory_mut()[node as usize].get_data_mut() }
+    }
+
+    /// relinks the node at `ptr` to the front in O(1), the payload isn't touched and `ptr` stays valid
+    /// # Panics
+    /// when `ptr` isn't linked into the list
+    #[track_caller]
+    fn move_to_front(&mut self, ptr: u32) {
+        assert!(self.is_linked(ptr), "move_to_front: node {} is not in the list", ptr);
+        if ptr != self.get_front() {
+            let (front, rear) = (self.get_front(), self.get_rear());
+            unsafe {
+                let (_, rear) = relink_between_ends(self.get_memory_mut(), ptr, front, rear);
+                self.set_ends(ptr, rear);
+            }
+        }
+    }
+
+    /// relinks the node at `ptr` to the rear in O(1), the payload isn't touched and `ptr` stays valid
+    /// # Panics
+    /// when `ptr` isn't linked into the list
+    #[track_caller]
+    fn move_to_rear(&mut self, ptr: u32) {
+        assert!(self.is_linked(ptr), "move_to_rear: node {} is not in the list", ptr);
+        if ptr != self.get_rear() {
+            let (front, rear) = (self.get_front(), self.get_rear());
+            unsafe {
+                let (front, _) = relink_between_ends(self.get_memory_mut(), ptr, front, rear);
+                self.set_ends(front, ptr);
+            }
+        }
+    }
+
     /// free node at location `node`
     fn free(&mut self, node: u32) {
         if self.get_pool() == !0 {
@@ -98,6 +576,29 @@ where
     }
 }
 
+/// unlinks `ptr` and links it back in between `rear` and `front`, returns the ends with `ptr` taken
+/// out so the caller can decide which end `ptr` becomes. `ptr` must be linked
+fn relink_between_ends<NodeType: LLNodeCoreOps>(
+    memory: &mut [NodeType],
+    ptr: u32,
+    mut front: u32,
+    mut rear: u32,
+) -> (u32, u32) {
+    let [prev, next] = *memory[ptr as usize].get_children();
+    memory[prev as usize].get_children_mut()[1] = next;
+    memory[next as usize].get_children_mut()[0] = prev;
+    if ptr == front {
+        front = next;
+    }
+    if ptr == rear {
+        rear = prev;
+    }
+    *memory[ptr as usize].get_children_mut() = [rear, front];
+    memory[rear as usize].get_children_mut()[1] = ptr;
+    memory[front as usize].get_children_mut()[0] = ptr;
+    (front, rear)
+}
+
 pub struct OptionNode<T> {
     data: Option<T>,
     children: [u32; 2],
@@ -183,8 +684,90 @@ where
     }
 }
 
+/// # Description
+/// Returned by `DoublyLinkedList::validate()`, says which invariant broke and where
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ListCorruption {
+    /// `front`/`rear` are null on a non-empty list or set on an empty one
+    BadEnds { front: u32, rear: u32 },
+    /// a link in `node` points outside of memory, `node` is `!0` when the bad link is
+    /// `front`, `rear` or `pool` itself
+    Dangling { node: u32, target: u32 },
+    /// `node` was reached twice while walking the list or the pool
+    Revisited { node: u32 },
+    /// the prev pointer of `node` doesn't point back at the node whose next pointer led to it
+    PrevMismatch { node: u32 },
+    /// walking `len` next pointers from `front` ended at `node` instead of `rear`,
+    /// or the next pointer of `rear` doesn't lead back to `front`
+    ForwardWalk { node: u32 },
+    /// walking prev pointers from `rear` ended at `node` instead of `front`
+    BackwardWalk { node: u32 },
+    /// `node` is in the pool but is also linked into the list
+    PoolOverlap { node: u32 },
+    /// `node` is neither linked into the list nor in the pool, it leaked
+    Unreachable { node: u32 },
+}
+
+/// # Description
+/// A node pointer that remembers which generation of its slot it was made for
+/// # Comments
+/// Raw `u32` pointers silently refer to a different item once their slot is freed and reused.
+/// A `NodePtr` gets `StalePointer` instead. Get one from `handle(..)` or the `*_by_handle` inserts
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodePtr {
+    index: u32,
+    generation: u32,
+}
+
+impl NodePtr {
+    /// the raw pointer, only meaningful while the handle isn't stale
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+/// # Description
+/// Returned when a `NodePtr` refers to a node that has been removed (and possibly reused) since
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StalePointer;
+
+/// # Description
+/// Returned by `LLOps::try_remove(..)` and `DoublyLinkedList::remove_by_handle(..)`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RemoveError {
+    /// the pointer is past the end of node memory
+    OutOfBounds,
+    /// the node exists but isn't in the list, it was already removed
+    NotLinked,
+    /// the handle was made for an earlier occupant of the slot
+    StaleGeneration,
+}
+
+/// # Description
+/// Returned by `DoublyLinkedList::from_bytes_with(..)` when the input isn't a valid encoding
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// the input ends before the header or the payloads it announces
+    Truncated,
+    /// there are bytes left after the last payload
+    TrailingBytes,
+    /// the header's element size isn't the one the caller decodes
+    ElementSize { expected: usize, found: usize },
+    /// the header announces more items than a list can hold
+    TooLong,
+    /// the header announces items with 0 byte payloads, nothing in the input backs their count
+    ZeroSizedElements,
+    /// the decoder rejected the payload at `index`
+    InvalidElement { index: usize },
+}
+
+/// count (u64) + element size (u32), little endian
+const BYTES_HEADER_LEN: usize = 12;
+
 pub struct DoublyLinkedList<NodeType> {
     memory: Vec<NodeType>,
+    /// bumped every time a slot in `memory` is reused, see `NodePtr`
+    generations: Vec<u32>,
     pub front: u32,
     pub rear: u32,
     pub pool: u32,
@@ -194,10 +777,12 @@ pub struct DoublyLinkedList<NodeType> {
 pub struct DLLNodeIterator<LinkedList> {
     dll: LinkedList,
     node: u32,
+    /// the next node handed out by `next_back()`
+    back: u32,
     len: u32,
 }
 
-impl<'a, NodeType> Iterator for DLLNodeIterator<&'a DoublyLinkedList<NodeType>>
+impl<NodeType> Iterator for DLLNodeIterator<&DoublyLinkedList<NodeType>>
 where
     NodeType: LLNodeCoreOps,
 {
@@ -212,12 +797,138 @@ where
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len as usize, Some(self.len as usize))
+    }
+}
+
+/// walks the prev pointers from the rear, `len` is shared with `next()` so both ends stop
+/// when they meet
+impl<NodeType> DoubleEndedIterator for DLLNodeIterator<&DoublyLinkedList<NodeType>>
+where
+    NodeType: LLNodeCoreOps,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            let old_node = self.back;
+            self.back = self.dll[old_node].get_children()[0];
+            self.len -= 1;
+            Some(old_node)
+        } else {
+            None
+        }
+    }
+}
+
+impl<NodeType> ExactSizeIterator for DLLNodeIterator<&DoublyLinkedList<NodeType>> where
+    NodeType: LLNodeCoreOps
+{
+}
+/// Returned by `LLOps::drain()`
+pub struct DLLDrain<'a, List, NodeType, DataType>
+where
+    List: LLOps<NodeType, DataType>,
+    NodeType: LLNodeOps<DataType> + LLNodeCoreOps,
+{
+    list: &'a mut List,
+    _node: PhantomData<(NodeType, DataType)>,
+}
+
+impl<'a, List, NodeType, DataType> Iterator for DLLDrain<'a, List, NodeType, DataType>
+where
+    List: LLOps<NodeType, DataType>,
+    NodeType: LLNodeOps<DataType> + LLNodeCoreOps,
+{
+    type Item = DataType;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<'a, List, NodeType, DataType> Drop for DLLDrain<'a, List, NodeType, DataType>
+where
+    List: LLOps<NodeType, DataType>,
+    NodeType: LLNodeOps<DataType> + LLNodeCoreOps,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// Consuming iterator returned by `into_iter()`, the node memory is dropped with it
+pub struct DLLIntoIter<NodeType, DataType> {
+    list: DoublyLinkedList<NodeType>,
+    _data: PhantomData<DataType>,
+}
+
+impl<NodeType, DataType> Iterator for DLLIntoIter<NodeType, DataType>
+where
+    DoublyLinkedList<NodeType>: LLOps<NodeType, DataType>,
+    NodeType: LLNodeOps<DataType> + LLNodeCoreOps,
+{
+    type Item = DataType;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<NodeType, DataType> DoubleEndedIterator for DLLIntoIter<NodeType, DataType>
+where
+    DoublyLinkedList<NodeType>: LLOps<NodeType, DataType>,
+    NodeType: LLNodeOps<DataType> + LLNodeCoreOps,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_rear()
+    }
+}
+
+impl<NodeType, DataType> ExactSizeIterator for DLLIntoIter<NodeType, DataType>
+where
+    DoublyLinkedList<NodeType>: LLOps<NodeType, DataType>,
+    NodeType: LLNodeOps<DataType> + LLNodeCoreOps,
+{
+}
+
+impl<T> IntoIterator for DoublyLinkedList<OptionNode<T>> {
+    type Item = T;
+    type IntoIter = DLLIntoIter<OptionNode<T>, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        DLLIntoIter {
+            list: self,
+            _data: PhantomData,
+        }
+    }
 }
+
+impl<T> IntoIterator for DoublyLinkedList<Node<T>>
+where
+    T: Default + Copy,
+{
+    type Item = T;
+    type IntoIter = DLLIntoIter<Node<T>, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        DLLIntoIter {
+            list: self,
+            _data: PhantomData,
+        }
+    }
+}
+
 #[allow(dead_code)]
 impl<NodeType> DoublyLinkedList<NodeType> {
     pub fn new() -> Self {
         Self {
             memory: Vec::new(),
+            generations: Vec::new(),
             front: !0,
             rear: !0,
             pool: !0,
@@ -225,84 +936,539 @@ impl<NodeType> DoublyLinkedList<NodeType> {
         }
     }
 }
+impl<NodeType> Default for DoublyLinkedList<NodeType> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[allow(dead_code)]
 impl<NodeType> DoublyLinkedList<NodeType>
 where
     NodeType: LLNodeCoreOps,
 {
-    pub fn node_index_iter(&self) -> impl Iterator<Item = u32> + '_ {
+    pub fn node_index_iter(&self) -> impl DoubleEndedIterator<Item = u32> + ExactSizeIterator + '_ {
         let node = self.front;
+        let back = self.rear;
         let len = self.len;
         DLLNodeIterator {
             dll: self,
             node,
+            back,
             len,
         }
     }
-    pub fn iter(&self) -> impl Iterator<Item = &NodeType> {
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &NodeType> + ExactSizeIterator {
         self.node_index_iter().map(move |index| &self[index])
     }
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut NodeType> {
-        let mem_ptr = self.memory.as_mut_ptr();
-        self.node_index_iter()
-            .map(move |index| unsafe { &mut *mem_ptr.offset(index as isize) })
-    }
-}
-
-impl<T> LLOps<OptionNode<T>, T> for DoublyLinkedList<OptionNode<T>> {
-    fn get_memory(&mut self) -> &Vec<OptionNode<T>> {
-        &self.memory
-    }
-
-    fn get_pool(&self) -> u32 {
-        self.pool
+    /// walks the list rear to front, same as `iter().rev()`
+    pub fn iter_rev(&self) -> impl DoubleEndedIterator<Item = &NodeType> + ExactSizeIterator {
+        self.iter().rev()
     }
-
-    unsafe fn get_memory_mut(&mut self) -> &mut Vec<OptionNode<T>> {
-        &mut self.memory
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut NodeType> + ExactSizeIterator {
+        self.node_iter_mut().map(|(_, node)| node)
     }
 
-    unsafe fn get_pool_mut(&mut self) -> &mut u32 {
-        &mut self.pool
+    /// walks front to rear yielding each node's pointer along with the node itself, mutably
+    /// # Panics
+    /// when the links (or `len`) are corrupt enough that a node comes up twice
+    fn node_iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (u32, &mut NodeType)> + ExactSizeIterator {
+        // links are read through `&self`, so the walk is done before borrowing memory mutably
+        let order = self.node_index_iter().collect::<Vec<_>>();
+        let mut slots = self.memory.iter_mut().map(Some).collect::<Vec<_>>();
+        order.into_iter().map(move |ptr| {
+            let node = slots[ptr as usize].take().expect("node visited twice");
+            (ptr, node)
+        })
     }
 
-    fn get_rear(&self) -> u32 {
-        self.rear
-    }
-    fn get_front(&self) -> u32 {
-        self.front
-    }
-    fn len(&self) -> usize {
-        self.len as usize
-    }
+    /// walks the list in both directions and the pool, checking that the links agree with
+    /// `len`, `front` and `rear` and that every node is either linked or pooled, exactly once
+    /// # Comments
+    /// O(n) in the size of memory, meant for tests and `debug_assert!`s
+    pub fn validate(&self) -> Result<(), ListCorruption> {
+        let (front, rear, len) = (self.front, self.rear, self.len as usize);
+        let memory = &self.memory;
+        let links = |node: u32| memory[node as usize].get_children();
+        let check = |node: u32, target: u32| {
+            if (target as usize) < memory.len() {
+                Ok(())
+            } else {
+                Err(ListCorruption::Dangling { node, target })
+            }
+        };
 
-    fn insert(&mut self, cur_node: u32, dir: usize, data: T) {
-        if self.len == 0 {
-            let new_node = self.allocate(data);
-            self[new_node].children[0] = new_node;
-            self[new_node].children[1] = new_node;
-            self.front = new_node;
-            self.rear = new_node;
-        } else {
-            let new_node = self.allocate(data);
-            let adj_node = self[cur_node].children[dir];
+        if (len == 0) != (front == !0) || (len == 0) != (rear == !0) {
+            return Err(ListCorruption::BadEnds { front, rear });
+        }
 
-            self[cur_node].children[dir] = new_node;
-            self[new_node].children[1 - dir] = cur_node;
+        // 0 = not seen, 1 = linked, 2 = pooled
+        let mut seen = vec![0u8; memory.len()];
+        if len > 0 {
+            check(!0, front)?;
+            check(!0, rear)?;
 
-            self[adj_node].children[1 - dir] = new_node;
-            self[new_node].children[dir] = adj_node;
+            let mut node = front;
+            for step in 1..=len {
+                if seen[node as usize] != 0 {
+                    return Err(ListCorruption::Revisited { node });
+                }
+                seen[node as usize] = 1;
+                let next = links(node)[1];
+                check(node, next)?;
+                if links(next)[0] != node {
+                    return Err(ListCorruption::PrevMismatch { node: next });
+                }
+                if step == len && (node != rear || next != front) {
+                    return Err(ListCorruption::ForwardWalk { node });
+                }
+                node = next;
+            }
 
-            if cur_node == self.front && dir == 0 {
-                self.front = new_node;
+            let mut node = rear;
+            for _ in 1..len {
+                node = links(node)[0];
+            }
+            if node != front {
+                return Err(ListCorruption::BackwardWalk { node });
             }
+        }
 
-            if cur_node == self.rear && dir == 1 {
-                self.rear = new_node;
+        let mut node = self.pool;
+        if node != !0 {
+            check(!0, node)?;
+        }
+        while node != !0 {
+            match seen[node as usize] {
+                1 => return Err(ListCorruption::PoolOverlap { node }),
+                2 => return Err(ListCorruption::Revisited { node }),
+                _ => seen[node as usize] = 2,
             }
+            if links(node)[1] != !0 {
+                return Err(ListCorruption::PoolOverlap { node });
+            }
+            let next = links(node)[0];
+            if next != !0 {
+                check(node, next)?;
+            }
+            node = next;
         }
-        self.len += 1;
-    }
+
+        match seen.iter().position(|&state| state == 0) {
+            Some(node) => Err(ListCorruption::Unreachable { node: node as u32 }),
+            None => Ok(()),
+        }
+    }
+
+    /// rewrites the nodes into a densely packed memory vector in traversal order (front at 0),
+    /// clearing the pool
+    /// # Returns
+    /// the `(old_ptr, new_ptr)` pairs of every node front to rear so outside pointers can be updated
+    /// # Comments
+    /// every `NodePtr` handle goes stale
+    pub fn compact(&mut self) -> Vec<(u32, u32)> {
+        let order = self.node_index_iter().collect::<Vec<_>>();
+        let len = order.len() as u32;
+
+        let mut old_memory = std::mem::take(&mut self.memory)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.memory = order
+            .iter()
+            .map(|&ptr| old_memory[ptr as usize].take().expect("node is linked once"))
+            .collect();
+        for (new_ptr, node) in (0..len).zip(self.memory.iter_mut()) {
+            *node.get_children_mut() = [(new_ptr + len - 1) % len, (new_ptr + 1) % len];
+        }
+
+        // bumping the generations makes every outstanding handle stale. The counters of the slots
+        // that got cut off are kept so handles into them stay stale once the memory grows back
+        self.generations.iter_mut().for_each(|g| *g = g.wrapping_add(1));
+        self.memory.shrink_to_fit();
+
+        self.pool = !0;
+        if len > 0 {
+            self.front = 0;
+            self.rear = len - 1;
+        }
+        order.into_iter().zip(0..len).collect()
+    }
+
+    /// gives a slot freshly pushed onto the end of `memory` its generation
+    /// # Comments
+    /// `generations` can be longer than `memory` after `compact()`, a slot that comes back gets
+    /// its old counter bumped instead of starting over at 0
+    fn claim_generation(&mut self, ptr: u32) {
+        match self.generations.get_mut(ptr as usize) {
+            Some(generation) => *generation = generation.wrapping_add(1),
+            None => self.generations.push(0),
+        }
+    }
+
+    /// returns a generational handle for the node at `ptr`, `None` when `ptr` isn't linked
+    pub fn handle<T>(&self, ptr: u32) -> Option<NodePtr>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+    {
+        if self.is_linked(ptr) {
+            Some(NodePtr {
+                index: ptr,
+                generation: self.generations[ptr as usize],
+            })
+        } else {
+            None
+        }
+    }
+
+    /// turns `handle` back into a raw pointer if its node is still in the list
+    pub fn resolve<T>(&self, handle: NodePtr) -> Result<u32, StalePointer>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+    {
+        let current = self.generations.get(handle.index as usize);
+        if current == Some(&handle.generation) && self.is_linked(handle.index) {
+            Ok(handle.index)
+        } else {
+            Err(StalePointer)
+        }
+    }
+
+    pub fn get_by_handle<'a, T>(&'a self, handle: NodePtr) -> Result<&'a T, StalePointer>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+        T: 'a,
+    {
+        let ptr = self.resolve(handle)?;
+        self[ptr].get_data().ok_or(StalePointer)
+    }
+
+    pub fn get_by_handle_mut<'a, T>(&'a mut self, handle: NodePtr) -> Result<&'a mut T, StalePointer>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+        T: 'a,
+    {
+        let ptr = self.resolve(handle)?;
+        self[ptr].get_data_mut().ok_or(StalePointer)
+    }
+
+    /// same as `try_remove(..)` but also checks the handle's generation
+    pub fn remove_by_handle<T>(&mut self, handle: NodePtr) -> Result<T, RemoveError>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+    {
+        match self.generations.get(handle.index as usize) {
+            Some(&generation) if generation != handle.generation => Err(RemoveError::StaleGeneration),
+            _ => self.try_remove(handle.index),
+        }
+    }
+
+    /// same as `insert_after(..)` but checks the handle first and hands out a handle
+    pub fn insert_after_by_handle<T>(&mut self, handle: NodePtr, data: T) -> Result<NodePtr, StalePointer>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+    {
+        let ptr = self.resolve(handle)?;
+        let new_node = self.insert_after(ptr, data);
+        self.handle(new_node).ok_or(StalePointer)
+    }
+
+    /// same as `insert_before(..)` but checks the handle first and hands out a handle
+    pub fn insert_before_by_handle<T>(&mut self, handle: NodePtr, data: T) -> Result<NodePtr, StalePointer>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+    {
+        let ptr = self.resolve(handle)?;
+        let new_node = self.insert_before(ptr, data);
+        self.handle(new_node).ok_or(StalePointer)
+    }
+
+    /// the items front to rear, without the nodes around them
+    fn data_iter<'a, T>(&'a self) -> impl DoubleEndedIterator<Item = &'a T> + 'a
+    where
+        NodeType: LLNodeOps<T>,
+        T: 'a,
+    {
+        self.iter().filter_map(|node| node.get_data())
+    }
+
+    /// walks front to rear yielding each node's pointer along with its data
+    pub fn iter_ptr<'a, T>(&'a self) -> impl DoubleEndedIterator<Item = (u32, &'a T)> + 'a
+    where
+        NodeType: LLNodeOps<T>,
+        T: 'a,
+    {
+        self.node_index_iter()
+            .filter_map(move |ptr| self[ptr].get_data().map(|data| (ptr, data)))
+    }
+
+    /// same as `iter_ptr` but the data is mutable
+    pub fn iter_ptr_mut<'a, T>(&'a mut self) -> impl DoubleEndedIterator<Item = (u32, &'a mut T)> + 'a
+    where
+        NodeType: LLNodeOps<T>,
+        T: 'a,
+    {
+        self.node_iter_mut()
+            .filter_map(|(ptr, node)| node.get_data_mut().map(|data| (ptr, data)))
+    }
+
+    /// returns the logical index of the node at `ptr` counting from the front (front is 0),
+    /// `None` when `ptr` isn't linked. O(index)
+    pub fn position_of<T>(&self, ptr: u32) -> Option<usize>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+    {
+        if !self.is_linked(ptr) {
+            return None;
+        }
+        self.node_index_iter().position(|node| node == ptr)
+    }
+
+    /// same as `position_of` but counts from the rear (rear is 0)
+    pub fn position_of_from_rear<T>(&self, ptr: u32) -> Option<usize>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+    {
+        if !self.is_linked(ptr) {
+            return None;
+        }
+        self.node_index_iter().rev().position(|node| node == ptr)
+    }
+
+    /// same as `position_of` but stale handles give `None`
+    pub fn position_of_handle<T>(&self, handle: NodePtr) -> Option<usize>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+    {
+        self.position_of(self.resolve(handle).ok()?)
+    }
+
+    /// encodes the items front to rear into a compact binary form
+    /// # Comments
+    /// The output is a header (item count as a u64 and `elem_size` as a u32, both little endian)
+    /// followed by one `elem_size` byte payload per item. `encode` fills each payload, the slice it
+    /// gets is zeroed and exactly `elem_size` long. Node pointers aren't stored, `from_bytes_with`
+    /// rebuilds them
+    pub fn to_bytes_with<T, F>(&self, elem_size: usize, mut encode: F) -> Vec<u8>
+    where
+        NodeType: LLNodeOps<T>,
+        F: FnMut(&T, &mut [u8]),
+    {
+        let mut bytes = vec![0; BYTES_HEADER_LEN + elem_size * self.len as usize];
+        let (header, payloads) = bytes.split_at_mut(BYTES_HEADER_LEN);
+        header[..8].copy_from_slice(&(self.len as u64).to_le_bytes());
+        header[8..].copy_from_slice(&(elem_size as u32).to_le_bytes());
+        if elem_size > 0 {
+            for (data, payload) in self.data_iter().zip(payloads.chunks_exact_mut(elem_size)) {
+                encode(data, payload);
+            }
+        }
+        bytes
+    }
+
+    /// rebuilds a compact list from the output of `to_bytes_with`
+    /// # Comments
+    /// `decode` gets each `elem_size` byte payload and returns `None` to reject it. Malformed input
+    /// gives a `DecodeError`, never a panic, and nothing is allocated before the lengths check out. \
+    /// An `elem_size` of 0 only decodes the empty list, a count with no bytes behind it can't be
+    /// trusted
+    pub fn from_bytes_with<T, F>(bytes: &[u8], elem_size: usize, mut decode: F) -> Result<Self, DecodeError>
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+        F: FnMut(&[u8]) -> Option<T>,
+    {
+        if bytes.len() < BYTES_HEADER_LEN {
+            return Err(DecodeError::Truncated);
+        }
+        let (header, payloads) = bytes.split_at(BYTES_HEADER_LEN);
+        let count = u64::from_le_bytes(header[..8].try_into().expect("8 byte slice"));
+        let found = u32::from_le_bytes(header[8..].try_into().expect("4 byte slice")) as usize;
+        if found != elem_size {
+            return Err(DecodeError::ElementSize {
+                expected: elem_size,
+                found,
+            });
+        }
+        // the null pointer `!0` can't be a node
+        if count >= u32::MAX as u64 {
+            return Err(DecodeError::TooLong);
+        }
+        if elem_size == 0 && count > 0 {
+            return Err(DecodeError::ZeroSizedElements);
+        }
+        let count = count as usize;
+        let payload_len = count.checked_mul(elem_size).ok_or(DecodeError::TooLong)?;
+        if payloads.len() < payload_len {
+            return Err(DecodeError::Truncated);
+        }
+        if payloads.len() > payload_len {
+            return Err(DecodeError::TrailingBytes);
+        }
+
+        let mut list = Self::new();
+        list.memory.reserve(count);
+        for index in 0..count {
+            let payload = &payloads[index * elem_size..(index + 1) * elem_size];
+            let data = decode(payload).ok_or(DecodeError::InvalidElement { index })?;
+            list.push_rear(data);
+        }
+        Ok(list)
+    }
+
+    /// walks from the node at `ptr` (included) toward the rear, empty when `ptr` isn't linked
+    pub fn iter_from<'a, T>(&'a self, ptr: u32) -> impl Iterator<Item = &'a T> + 'a
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+        T: 'a,
+    {
+        self.walk_from(ptr, self.rear, 1)
+    }
+
+    /// walks from the node at `ptr` (included) toward the front, empty when `ptr` isn't linked
+    pub fn iter_from_rev<'a, T>(&'a self, ptr: u32) -> impl Iterator<Item = &'a T> + 'a
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+        T: 'a,
+    {
+        self.walk_from(ptr, self.front, 0)
+    }
+
+    /// follows `children[dir]` from `ptr` up to and including `last`
+    fn walk_from<'a, T>(&'a self, ptr: u32, last: u32, dir: usize) -> impl Iterator<Item = &'a T> + 'a
+    where
+        Self: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T>,
+        T: 'a,
+    {
+        let start = Some(ptr).filter(|&ptr| self.is_linked(ptr));
+        std::iter::successors(start, move |&node| {
+            Some(self[node].get_children()[dir]).filter(|_| node != last)
+        })
+        .filter_map(move |node| self[node].get_data())
+    }
+
+    /// moves all of `other`'s nodes to the rear of `self`, leaving `other` empty
+    /// # Comments
+    /// `other`'s memory is moved over in one go and its pointers (including its pool) are shifted
+    /// by the old length of `self.memory`. Payloads are never cloned. \
+    /// Pointers previously handed out by `other` are `ptr + offset` afterwards where offset is the
+    /// length of `self`'s memory before the call. `NodePtr`s from `other` don't carry over.
+    pub fn append(&mut self, other: &mut Self) {
+        let offset = self.memory.len() as u32;
+        let shift = |ptr: u32| if ptr == !0 { ptr } else { ptr + offset };
+
+        // other keeps its counters so its old handles stay stale when it fills up again
+        let moved = other.memory.len() as u32;
+        self.memory.append(&mut other.memory);
+        (offset..offset + moved).for_each(|ptr| self.claim_generation(ptr));
+        for node in self.memory[offset as usize..].iter_mut() {
+            node.get_children_mut().iter_mut().for_each(|e| *e = shift(*e));
+        }
+
+        // chain other's pool in front of ours
+        if other.pool != !0 {
+            let mut last = shift(other.pool);
+            while self[last].get_children()[0] != !0 {
+                last = self[last].get_children()[0];
+            }
+            self[last].get_children_mut()[0] = self.pool;
+            self.pool = shift(other.pool);
+        }
+
+        if other.len > 0 {
+            let (other_front, other_rear) = (shift(other.front), shift(other.rear));
+            if self.len == 0 {
+                self.front = other_front;
+            } else {
+                let (front, rear) = (self.front, self.rear);
+                self[rear].get_children_mut()[1] = other_front;
+                self[other_front].get_children_mut()[0] = rear;
+                self[other_rear].get_children_mut()[1] = front;
+                self[front].get_children_mut()[0] = other_rear;
+            }
+            self.rear = other_rear;
+            self.len += other.len;
+        }
+
+        other.front = !0;
+        other.rear = !0;
+        other.pool = !0;
+        other.len = 0;
+    }
+}
+
+impl<T> LLOps<OptionNode<T>, T> for DoublyLinkedList<OptionNode<T>> {
+    fn get_memory(&self) -> &Vec<OptionNode<T>> {
+        &self.memory
+    }
+
+    fn get_pool(&self) -> u32 {
+        self.pool
+    }
+
+    unsafe fn get_memory_mut(&mut self) -> &mut Vec<OptionNode<T>> {
+        &mut self.memory
+    }
+
+    unsafe fn get_pool_mut(&mut self) -> &mut u32 {
+        &mut self.pool
+    }
+
+    unsafe fn set_ends(&mut self, front: u32, rear: u32) {
+        self.front = front;
+        self.rear = rear;
+    }
+
+    fn get_rear(&self) -> u32 {
+        self.rear
+    }
+    fn get_front(&self) -> u32 {
+        self.front
+    }
+    fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    fn insert(&mut self, cur_node: u32, dir: usize, data: T) {
+        if self.len == 0 {
+            let new_node = self.allocate(data);
+            self[new_node].children[0] = new_node;
+            self[new_node].children[1] = new_node;
+            self.front = new_node;
+            self.rear = new_node;
+        } else {
+            let new_node = self.allocate(data);
+            let adj_node = self[cur_node].children[dir];
+
+            self[cur_node].children[dir] = new_node;
+            self[new_node].children[1 - dir] = cur_node;
+
+            self[adj_node].children[1 - dir] = new_node;
+            self[new_node].children[dir] = adj_node;
+
+            if cur_node == self.front && dir == 0 {
+                self.front = new_node;
+            }
+
+            if cur_node == self.rear && dir == 1 {
+                self.rear = new_node;
+            }
+        }
+        self.len += 1;
+    }
 
     fn remove(&mut self, cur_node: u32) -> Option<T> {
         if self.len == 0 {
@@ -324,6 +1490,11 @@ impl<T> LLOps<OptionNode<T>, T> for DoublyLinkedList<OptionNode<T>> {
                 self.rear = ln;
             }
 
+            if self.len == 0 {
+                self.front = !0;
+                self.rear = !0;
+            }
+
             item
         }
     }
@@ -331,9 +1502,12 @@ impl<T> LLOps<OptionNode<T>, T> for DoublyLinkedList<OptionNode<T>> {
     fn allocate(&mut self, data: T) -> u32 {
         if self.pool == !0 {
             self.memory.push(OptionNode::default().with_data(data));
-            self.memory.len() as u32 - 1
+            let ptr = self.memory.len() as u32 - 1;
+            self.claim_generation(ptr);
+            ptr
         } else {
             let old_pool = self.pool;
+            self.generations[old_pool as usize] = self.generations[old_pool as usize].wrapping_add(1);
             let new_pool = self[old_pool].children[0];
             self[old_pool].data = Some(data);
             self[old_pool].nullify();
@@ -347,7 +1521,7 @@ impl<T> LLOps<Node<T>, T> for DoublyLinkedList<Node<T>>
 where
     T: Default + Copy,
 {
-    fn get_memory(&mut self) -> &Vec<Node<T>> {
+    fn get_memory(&self) -> &Vec<Node<T>> {
         &self.memory
     }
     fn get_pool(&self) -> u32 {
@@ -371,6 +1545,11 @@ where
         &mut self.pool
     }
 
+    unsafe fn set_ends(&mut self, front: u32, rear: u32) {
+        self.front = front;
+        self.rear = rear;
+    }
+
     fn insert(&mut self, cur_node: u32, dir: usize, data: T) {
         if self.len == 0 {
             let new_node = self.allocate(data);
@@ -419,6 +1598,11 @@ where
                 self.rear = ln;
             }
 
+            if self.len == 0 {
+                self.front = !0;
+                self.rear = !0;
+            }
+
             Some(item)
         }
     }
@@ -426,9 +1610,12 @@ where
     fn allocate(&mut self, data: T) -> u32 {
         if self.pool == !0 {
             self.memory.push(Node::default().with_data(data));
-            self.memory.len() as u32 - 1
+            let ptr = self.memory.len() as u32 - 1;
+            self.claim_generation(ptr);
+            ptr
         } else {
             let old_pool = self.pool;
+            self.generations[old_pool as usize] = self.generations[old_pool as usize].wrapping_add(1);
             let new_pool = self[old_pool].children[0];
             self[old_pool].data = data;
             self[old_pool].nullify();
@@ -438,6 +1625,186 @@ where
     }
 }
 
+/// pushes onto the rear, node memory is reserved up front from the iterator's size hint
+impl<NodeType, T> Extend<T> for DoublyLinkedList<NodeType>
+where
+    Self: LLOps<NodeType, T>,
+    NodeType: LLNodeOps<T> + LLNodeCoreOps,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.memory.reserve(iter.size_hint().0);
+        self.generations.reserve(iter.size_hint().0);
+        iter.for_each(|data| self.push_rear(data));
+    }
+}
+
+/// the first item ends up at the front
+impl<NodeType, T> FromIterator<T> for DoublyLinkedList<NodeType>
+where
+    Self: LLOps<NodeType, T>,
+    NodeType: LLNodeOps<T> + LLNodeCoreOps,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+/// the clone is compact, freed nodes in the pool aren't copied over
+impl<T> Clone for DoublyLinkedList<OptionNode<T>>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.data_iter().cloned().collect()
+    }
+}
+
+impl<T> Clone for DoublyLinkedList<Node<T>>
+where
+    T: Copy + Default,
+{
+    fn clone(&self) -> Self {
+        self.data_iter().copied().collect()
+    }
+}
+
+impl<T> fmt::Debug for DoublyLinkedList<OptionNode<T>>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.data_iter()).finish()
+    }
+}
+
+impl<T> fmt::Debug for DoublyLinkedList<Node<T>>
+where
+    T: fmt::Debug + Copy + Default,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.data_iter()).finish()
+    }
+}
+
+/// lists are equal when their items are, front to rear. Where the nodes live in memory doesn't matter
+impl<T> PartialEq for DoublyLinkedList<OptionNode<T>>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.data_iter().eq(other.data_iter())
+    }
+}
+
+impl<T> Eq for DoublyLinkedList<OptionNode<T>> where T: Eq {}
+
+impl<T> PartialEq for DoublyLinkedList<Node<T>>
+where
+    T: PartialEq + Copy + Default,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.data_iter().eq(other.data_iter())
+    }
+}
+
+impl<T> Eq for DoublyLinkedList<Node<T>> where T: Eq + Copy + Default {}
+
+/// # Description
+/// `serde` support, enabled with the `serde` feature
+/// # Comments
+/// A list is written as a plain seq of its items front to rear. Node pointers are meaningless
+/// in another process so they never hit the wire, deserializing rebuilds compact node memory
+#[cfg(feature = "serde")]
+mod list_serde {
+    use super::*;
+    use serde::{
+        de::{SeqAccess, Visitor},
+        ser::SerializeSeq,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::fmt;
+
+    fn serialize_list<S, NodeType, T>(
+        list: &DoublyLinkedList<NodeType>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        NodeType: LLNodeOps<T> + LLNodeCoreOps,
+        T: Serialize,
+    {
+        let mut seq = serializer.serialize_seq(Some(list.len as usize))?;
+        for data in list.iter().filter_map(|node| node.get_data()) {
+            seq.serialize_element(data)?;
+        }
+        seq.end()
+    }
+
+    impl<T> Serialize for DoublyLinkedList<OptionNode<T>>
+    where
+        T: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize_list(self, serializer)
+        }
+    }
+
+    impl<T> Serialize for DoublyLinkedList<Node<T>>
+    where
+        T: Serialize + Copy + Default,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize_list(self, serializer)
+        }
+    }
+
+    struct ListVisitor<NodeType, T>(PhantomData<(NodeType, T)>);
+
+    impl<'de, NodeType, T> Visitor<'de> for ListVisitor<NodeType, T>
+    where
+        DoublyLinkedList<NodeType>: LLOps<NodeType, T>,
+        NodeType: LLNodeOps<T> + LLNodeCoreOps,
+        T: Deserialize<'de>,
+    {
+        type Value = DoublyLinkedList<NodeType>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a sequence of list items")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut list = DoublyLinkedList::new();
+            // don't trust the hint too much, it comes from the input
+            list.memory.reserve(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(data) = seq.next_element()? {
+                list.push_rear(data);
+            }
+            Ok(list)
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for DoublyLinkedList<OptionNode<T>>
+    where
+        T: Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_seq(ListVisitor(PhantomData))
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for DoublyLinkedList<Node<T>>
+    where
+        T: Deserialize<'de> + Copy + Default,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_seq(ListVisitor(PhantomData))
+        }
+    }
+}
+
 impl<NodeType> Index<u32> for DoublyLinkedList<NodeType> {
     type Output = NodeType;
     fn index(&self, index: u32) -> &Self::Output {
@@ -455,10 +1822,12 @@ impl<NodeType> IndexMut<u32> for DoublyLinkedList<NodeType> {
 /// Basic sanity checks to make sure linked-list is functioning properly
 /// Testing is not even close to being extensive right now
 #[test]
+#[allow(clippy::map_clone)]
 fn push_pop_tests() {
     let mut ll: LinkedList<i32> = LinkedList::new();
 
     let to_vec = |ll: &LinkedList<_>| -> Vec<_> {
+        assert_eq!(ll.validate(), Ok(()));
         ll.iter()
             .map(|a| a.get_data().map(|&a| a))
             .collect::<Vec<_>>()
@@ -481,3 +1850,1168 @@ fn push_pop_tests() {
     ll.pop_rear();
     assert_eq!(to_vec(&ll), [Some(1)]);
 }
+
+/// # Description
+/// Uses both ends of the list like a deque and compares it against `VecDeque`
+#[test]
+fn deque_tests() {
+    use std::collections::VecDeque;
+
+    fn check<NodeType>(ll: &DoublyLinkedList<NodeType>, expected: &VecDeque<i32>)
+    where
+        NodeType: LLNodeOps<i32> + LLNodeCoreOps,
+    {
+        let items: Vec<i32> = ll.iter().map(|n| *n.get_data().unwrap()).collect();
+        assert_eq!(items, expected.iter().copied().collect::<Vec<_>>());
+        assert_eq!(ll.len as usize, expected.len());
+        assert_eq!(ll.validate(), Ok(()));
+    }
+
+    let mut ll: LinkedList<i32> = LinkedList::new();
+    let mut packed: PackedLinkedList<i32> = PackedLinkedList::new();
+    let mut expected = VecDeque::new();
+
+    // small LCG so the test doesn't need a rand dependency
+    let mut state: u32 = 7;
+    for k in 0..2000 {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        match state >> 30 {
+            0 => {
+                ll.push_front(k);
+                packed.push_front(k);
+                expected.push_front(k);
+            }
+            1 => {
+                ll.push_rear(k);
+                packed.push_rear(k);
+                expected.push_back(k);
+            }
+            2 => {
+                let popped = expected.pop_front();
+                assert_eq!(ll.pop_front(), popped);
+                assert_eq!(packed.pop_front(), popped);
+            }
+            _ => {
+                let popped = expected.pop_back();
+                assert_eq!(ll.pop_rear(), popped);
+                assert_eq!(packed.pop_rear(), popped);
+            }
+        }
+        check(&ll, &expected);
+        check(&packed, &expected);
+    }
+
+    // draining from the front and refilling from the rear reuses freed nodes
+    while ll.pop_front().is_some() {}
+    assert!(ll.is_empty());
+    assert_eq!((ll.front, ll.rear), (!0, !0));
+    assert_eq!(ll.pop_front(), None);
+    assert_eq!(ll.pop_rear(), None);
+    let allocated = ll.memory.len();
+    for k in 0..3 {
+        ll.push_rear(k);
+    }
+    assert_eq!(ll.memory.len(), allocated);
+    assert_eq!(ll.pop_front(), Some(0));
+    assert_eq!(ll.pop_rear(), Some(2));
+    assert_eq!(ll.pop_front(), Some(1));
+}
+
+#[test]
+fn peek_tests() {
+    let mut ll: LinkedList<String> = LinkedList::new();
+    assert_eq!(ll.peek_front(), None);
+    assert_eq!(ll.peek_rear_mut(), None);
+
+    ll.push_front(String::from("b"));
+    assert_eq!(ll.peek_front(), ll.peek_rear());
+    ll.push_front(String::from("a"));
+    ll.push_rear(String::from("c"));
+    assert_eq!(ll.peek_front().map(String::as_str), Some("a"));
+    assert_eq!(ll.peek_rear().map(String::as_str), Some("c"));
+
+    ll.peek_front_mut().unwrap().push('!');
+    ll.peek_rear_mut().unwrap().push('?');
+    assert_eq!(ll.pop_front().as_deref(), Some("a!"));
+    assert_eq!(ll.pop_rear().as_deref(), Some("c?"));
+    assert_eq!(ll.peek_front().map(String::as_str), Some("b"));
+    ll.pop_rear();
+    assert_eq!(ll.peek_rear(), None);
+
+    let mut packed: PackedLinkedList<u8> = PackedLinkedList::new();
+    assert_eq!(packed.peek_front(), None);
+    packed.push_rear(1);
+    packed.push_rear(2);
+    *packed.peek_rear_mut().unwrap() += 1;
+    assert_eq!((packed.peek_front(), packed.peek_rear()), (Some(&1), Some(&3)));
+}
+
+#[test]
+fn double_ended_iter_tests() {
+    let mut ll: LinkedList<i32> = LinkedList::new();
+    assert_eq!(ll.iter_rev().next().map(|n| n.get_data()), None);
+    for k in 0..6 {
+        ll.push_rear(k);
+    }
+    ll.pop_front();
+    ll.push_front(-1);
+
+    let data = |n: &OptionNode<i32>| *n.get_data().unwrap();
+    assert_eq!(ll.iter_rev().map(data).collect::<Vec<_>>(), [5, 4, 3, 2, 1, -1]);
+    assert_eq!(ll.iter().rev().map(data).collect::<Vec<_>>(), [5, 4, 3, 2, 1, -1]);
+    assert_eq!(ll.iter().len(), 6);
+
+    // both ends meet in the middle without handing out a node twice
+    {
+        let mut iter = ll.iter().map(data);
+        assert_eq!(iter.next(), Some(-1));
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    for (k, node) in ll.iter_mut().rev().enumerate() {
+        *node.get_data_mut().unwrap() = k as i32;
+    }
+    assert_eq!(ll.iter().map(data).collect::<Vec<_>>(), [5, 4, 3, 2, 1, 0]);
+
+    // borrows from both ends held at once
+    {
+        let mut iter = ll.iter_mut();
+        let (front, rear) = (iter.next().unwrap(), iter.next_back().unwrap());
+        assert_eq!(iter.len(), 4);
+        std::mem::swap(front.get_data_mut().unwrap(), rear.get_data_mut().unwrap());
+        for node in iter {
+            *node.get_data_mut().unwrap() *= 10;
+        }
+    }
+    assert_eq!(ll.iter().map(data).collect::<Vec<_>>(), [0, 40, 30, 20, 10, 5]);
+    assert_eq!(ll.validate(), Ok(()));
+}
+
+#[test]
+#[should_panic(expected = "node visited twice")]
+fn iter_mut_corrupt_len_test() {
+    let mut ll: LinkedList<i32> = (0..2).collect();
+    ll.len = 4;
+    let _ = ll.iter_mut().collect::<Vec<_>>();
+}
+
+#[test]
+#[should_panic(expected = "node visited twice")]
+fn iter_ptr_mut_corrupt_len_test() {
+    let mut ll: LinkedList<i32> = (0..2).collect();
+    ll.len = 4;
+    let _ = ll.iter_ptr_mut().collect::<Vec<_>>();
+}
+
+#[test]
+fn insert_after_before_tests() {
+    let mut ll: LinkedList<i32> = LinkedList::new();
+    let to_vec = |ll: &LinkedList<i32>| {
+        assert_eq!(ll.validate(), Ok(()));
+        ll.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>()
+    };
+
+    ll.push_rear(10);
+    let front = ll.front;
+    let rear = ll.insert_after(front, 30);
+    assert_eq!(ll.rear, rear);
+    let mid = ll.insert_before(rear, 20);
+    let new_front = ll.insert_before(front, 0);
+    assert_eq!(ll.front, new_front);
+    let new_rear = ll.insert_after(rear, 40);
+    assert_eq!(ll.rear, new_rear);
+    assert_eq!(to_vec(&ll), [0, 10, 20, 30, 40]);
+    assert_eq!(ll[mid].get_data(), Some(&20));
+    assert_eq!(ll.iter_rev().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>(), [40, 30, 20, 10, 0]);
+
+    // freed slots get reused and the returned pointer refers to the new node
+    assert_eq!(ll.remove(mid), Some(20));
+    assert!(!ll.is_linked(mid));
+    let reused = ll.insert_after(ll.front, 5);
+    assert_eq!(reused, mid);
+    assert_eq!(to_vec(&ll), [0, 5, 10, 30, 40]);
+
+    // keeping a list sorted incrementally
+    let mut sorted: PackedLinkedList<i32> = PackedLinkedList::new();
+    for &v in [5, 1, 9, 3, 7, 3, 0, 10].iter() {
+        let bigger = sorted.node_index_iter().find(|&p| *sorted[p].get_data().unwrap() > v);
+        match bigger {
+            Some(p) => sorted.insert_before(p, v),
+            None if sorted.is_empty() => {
+                sorted.push_rear(v);
+                sorted.rear
+            }
+            None => sorted.insert_after(sorted.rear, v),
+        };
+    }
+    let items = sorted.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>();
+    assert_eq!(items, [0, 1, 3, 3, 5, 7, 9, 10]);
+}
+
+#[test]
+#[should_panic]
+fn insert_after_stale_pointer_test() {
+    let mut ll: LinkedList<i32> = LinkedList::new();
+    ll.push_rear(1);
+    ll.push_rear(2);
+    let rear = ll.rear;
+    ll.pop_rear();
+    ll.insert_after(rear, 3);
+}
+
+#[test]
+fn split_off_tests() {
+    let to_vec = |ll: &LinkedList<String>| ll.iter().map(|n| n.get_data().unwrap().clone()).collect::<Vec<_>>();
+    let mut ll: LinkedList<String> = LinkedList::new();
+    for k in 0..6 {
+        ll.push_rear(k.to_string());
+    }
+
+    let third = ll.node_index_iter().nth(3).unwrap();
+    let tail = ll.split_off(third);
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(tail.validate(), Ok(()));
+    assert_eq!(to_vec(&ll), ["0", "1", "2"]);
+    assert_eq!(to_vec(&tail), ["3", "4", "5"]);
+
+    // splitting at the rear moves a single node
+    let rear = ll.rear;
+    let mut tail2 = ll.split_off(rear);
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(tail2.validate(), Ok(()));
+    assert_eq!(to_vec(&ll), ["0", "1"]);
+    assert_eq!(to_vec(&tail2), ["2"]);
+
+    // splitting at the front moves everything
+    let front = ll.front;
+    let all = ll.split_off(front);
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(all.validate(), Ok(()));
+    assert!(ll.is_empty());
+    assert_eq!(to_vec(&all), ["0", "1"]);
+
+    // both halves stay usable
+    ll.push_rear("a".to_string());
+    tail2.push_front("b".to_string());
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(tail2.validate(), Ok(()));
+    assert_eq!(to_vec(&ll), ["a"]);
+    assert_eq!(to_vec(&tail2), ["b", "2"]);
+
+    let mut packed: PackedLinkedList<u8> = PackedLinkedList::new();
+    for k in 0..4 {
+        packed.push_rear(k);
+    }
+    let ptr = packed.node_index_iter().nth(1).unwrap();
+    let packed_tail = packed.split_off(ptr);
+    assert_eq!(packed.validate(), Ok(()));
+    assert_eq!(packed_tail.validate(), Ok(()));
+    assert_eq!(packed_tail.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>(), [1, 2, 3]);
+}
+
+#[test]
+fn append_tests() {
+    let to_vec = |ll: &LinkedList<String>| ll.iter().map(|n| n.get_data().unwrap().clone()).collect::<Vec<_>>();
+    let list = |items: &[&str]| {
+        let mut ll: LinkedList<String> = LinkedList::new();
+        items.iter().for_each(|s| ll.push_rear(s.to_string()));
+        ll
+    };
+
+    // empty -> nonempty
+    let mut a = list(&["a", "b"]);
+    let mut empty = list(&[]);
+    a.append(&mut empty);
+    assert_eq!(a.validate(), Ok(()));
+    assert_eq!(empty.validate(), Ok(()));
+    assert_eq!(to_vec(&a), ["a", "b"]);
+
+    // nonempty -> empty
+    let mut empty = list(&[]);
+    let mut b = list(&["a", "b"]);
+    empty.append(&mut b);
+    assert_eq!(empty.validate(), Ok(()));
+    assert_eq!(b.validate(), Ok(()));
+    assert_eq!(to_vec(&empty), ["a", "b"]);
+    assert!(b.is_empty());
+
+    // two populated lists, both with free slots
+    let mut a = list(&["x", "a", "b"]);
+    let mut b = list(&["y", "c", "d", "z"]);
+    a.pop_front();
+    b.pop_front();
+    b.pop_rear();
+    let c_ptr = b.front;
+    let offset = a.memory.len() as u32;
+    a.append(&mut b);
+    assert_eq!(a.validate(), Ok(()));
+    assert_eq!(to_vec(&a), ["a", "b", "c", "d"]);
+    assert_eq!(a.iter_rev().map(|n| n.get_data().unwrap().as_str()).collect::<Vec<_>>(), ["d", "c", "b", "a"]);
+    assert_eq!(a[c_ptr + offset].get_data().map(|s| s.as_str()), Some("c"));
+
+    // all three free slots are reused before memory grows
+    let mem_len = a.memory.len();
+    for s in ["e", "f", "g"].iter() {
+        a.push_rear(s.to_string());
+    }
+    assert_eq!(a.memory.len(), mem_len);
+    a.push_rear("h".to_string());
+    assert_eq!(a.memory.len(), mem_len + 1);
+    assert_eq!(a.validate(), Ok(()));
+
+    // the emptied list is still usable
+    b.push_rear("q".to_string());
+    assert_eq!(b.validate(), Ok(()));
+    assert_eq!(to_vec(&b), ["q"]);
+}
+
+#[test]
+fn drain_tests() {
+    let mut ll: LinkedList<String> = LinkedList::new();
+    assert_eq!(ll.drain().next(), None);
+
+    for k in 0..5 {
+        ll.push_rear(k.to_string());
+    }
+    let mem_len = ll.memory.len();
+    assert_eq!(ll.drain().collect::<Vec<_>>(), ["0", "1", "2", "3", "4"]);
+    assert!(ll.is_empty());
+    assert_eq!(ll.validate(), Ok(()));
+
+    // rebuilding reuses the drained nodes
+    for k in 0..5 {
+        ll.push_front(k.to_string());
+    }
+    assert_eq!(ll.memory.len(), mem_len);
+    assert_eq!(ll.validate(), Ok(()));
+
+    // dropping early still clears the rest
+    {
+        let mut drain = ll.drain();
+        assert_eq!(drain.size_hint(), (5, Some(5)));
+        assert_eq!(drain.next().as_deref(), Some("4"));
+    }
+    assert!(ll.is_empty());
+    assert_eq!(ll.validate(), Ok(()));
+
+    let mut packed: PackedLinkedList<u16> = PackedLinkedList::new();
+    packed.push_rear(1);
+    packed.push_rear(2);
+    assert_eq!(packed.drain().sum::<u16>(), 3);
+    assert!(packed.is_empty());
+}
+
+#[test]
+fn retain_tests() {
+    let mut ll: LinkedList<u32> = LinkedList::new();
+    let mut packed: PackedLinkedList<u32> = PackedLinkedList::new();
+    let mut expected = Vec::new();
+    let items = |ll: &LinkedList<u32>| ll.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>();
+
+    ll.retain(|_| false);
+    assert_eq!(ll.validate(), Ok(()));
+
+    let mut state: u32 = 11;
+    for _ in 0..200 {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        let v = state >> 24;
+        ll.push_rear(v);
+        packed.push_rear(v);
+        expected.push(v);
+    }
+
+    // first and last elements both get removed here
+    let (first, last) = (expected[0], expected[expected.len() - 1]);
+    let keep = |v: &u32| v % 3 == 1 && *v != first && *v != last;
+    ll.retain(keep);
+    packed.retain(keep);
+    expected.retain(keep);
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(packed.validate(), Ok(()));
+    assert_eq!(items(&ll), expected);
+    assert_eq!(packed.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>(), expected);
+    assert_eq!(ll.peek_front(), expected.first());
+    assert_eq!(ll.peek_rear(), expected.last());
+
+    ll.retain_mut(|v| {
+        *v *= 2;
+        *v % 4 == 2
+    });
+    expected.retain_mut(|v| {
+        *v *= 2;
+        *v % 4 == 2
+    });
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(items(&ll), expected);
+
+    // removing everything
+    let mem_len = ll.memory.len();
+    ll.retain(|_| false);
+    assert!(ll.is_empty());
+    assert_eq!(ll.validate(), Ok(()));
+    for k in 0..mem_len as u32 {
+        ll.push_rear(k);
+    }
+    assert_eq!(ll.memory.len(), mem_len);
+}
+
+#[test]
+fn find_tests() {
+    let mut ll: LinkedList<&str> = LinkedList::new();
+    assert_eq!(ll.find(|_| true), None);
+    for s in ["apple", "kiwi", "avocado", "fig", "apricot"].iter() {
+        ll.push_rear(*s);
+    }
+
+    let starts_with_a = |s: &&str| s.starts_with('a');
+    let first = ll.find(starts_with_a).unwrap();
+    assert_eq!(first, ll.front);
+    let second = ll.find_from(first, starts_with_a).unwrap();
+    assert_eq!(ll[second].get_data(), Some(&"avocado"));
+    let third = ll.find_from(second, starts_with_a).unwrap();
+    assert_eq!(third, ll.rear);
+    assert_eq!(ll.find_from(third, starts_with_a), None);
+    assert_eq!(ll.find(|s| s.is_empty()), None);
+
+    // positional edits through the found pointers
+    let fig = ll.find(|s| *s == "fig").unwrap();
+    ll.insert_after(fig, "grape");
+    ll.remove(second);
+    assert_eq!(ll.find_from(second, |_| true), None);
+    let items = ll.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>();
+    assert_eq!(items, ["apple", "kiwi", "fig", "grape", "apricot"]);
+}
+
+#[test]
+fn from_iter_extend_tests() {
+    let words = ["a", "b", "c"];
+    let mut ll: LinkedList<String> = words.iter().map(|s| s.to_string()).collect();
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(ll.iter().map(|n| n.get_data().unwrap().as_str()).collect::<Vec<_>>(), words);
+
+    ll.extend(vec!["d".to_string(), "e".to_string()]);
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(
+        ll.iter().map(|n| n.get_data().unwrap().as_str()).collect::<Vec<_>>(),
+        ["a", "b", "c", "d", "e"]
+    );
+
+    let packed: PackedLinkedList<u64> = (0..1000).collect();
+    assert_eq!(packed.validate(), Ok(()));
+    assert_eq!(packed.memory.capacity(), 1000);
+    assert!(packed.iter().map(|n| *n.get_data().unwrap()).eq(0..1000));
+
+    let empty: LinkedList<u8> = std::iter::empty().collect();
+    assert!(empty.is_empty());
+    assert_eq!(empty.validate(), Ok(()));
+}
+
+#[test]
+fn into_iter_tests() {
+    let ll: LinkedList<String> = (0..5).map(|k| k.to_string()).collect();
+    assert_eq!(ll.into_iter().collect::<Vec<_>>(), ["0", "1", "2", "3", "4"]);
+
+    let mut ll: LinkedList<String> = (0..5).map(|k| k.to_string()).collect();
+    ll.pop_front();
+    ll.push_rear("5".to_string());
+    let mut iter = ll.into_iter();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next().as_deref(), Some("1"));
+    assert_eq!(iter.next_back().as_deref(), Some("5"));
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    assert_eq!(iter.rev().collect::<Vec<_>>(), ["4", "3", "2"]);
+
+    let packed: PackedLinkedList<i8> = (-3..3).collect();
+    assert_eq!(packed.into_iter().rev().collect::<Vec<_>>(), [2, 1, 0, -1, -2, -3]);
+
+    let mut total = 0;
+    for k in LinkedList::<i32>::new() {
+        total += k;
+    }
+    assert_eq!(total, 0);
+}
+
+#[test]
+fn pool_reuse_tests() {
+    let mut ll: LinkedList<String> = (0..8).map(|k| k.to_string()).collect();
+    assert_eq!(ll.pool_len(), 0);
+
+    let mut state: u32 = 3;
+    for k in 0..1000 {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        // churn like an lru cache: drop something, add something
+        let victim = ll.node_index_iter().nth((state >> 16) as usize % ll.len()).unwrap();
+        ll.remove(victim);
+        assert_eq!(ll.pool_len(), 1);
+        ll.push_front(k.to_string());
+        assert_eq!(ll.pool_len(), 0);
+    }
+    assert_eq!(ll.memory.len(), 8);
+    assert_eq!(ll.validate(), Ok(()));
+
+    ll.drain().for_each(drop);
+    assert_eq!(ll.pool_len(), 8);
+    ll.push_rear("x".to_string());
+    assert_eq!(ll.pool_len(), 7);
+}
+
+#[test]
+fn sort_tests() {
+    let mut empty: LinkedList<i32> = LinkedList::new();
+    empty.sort();
+    assert_eq!(empty.validate(), Ok(()));
+
+    let mut one: LinkedList<i32> = std::iter::once(4).collect();
+    one.sort();
+    assert_eq!(one.validate(), Ok(()));
+    assert_eq!(one.peek_front(), Some(&4));
+
+    let mut state: u32 = 5;
+    for &len in [2, 3, 7, 64, 100, 1000].iter() {
+        // (priority, arrival) pairs sorted by priority only, so ties show whether the sort is stable
+        let mut events = Vec::new();
+        for arrival in 0..len {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            events.push(((state >> 28) as u8, arrival));
+        }
+        let mut ll: LinkedList<(u8, i32)> = LinkedList::new();
+        // some removals first so the nodes aren't laid out in order
+        ll.push_rear((0, -1));
+        ll.extend(events.iter().copied());
+        ll.pop_front();
+        let first_ptr = ll.front;
+        let first = *ll.peek_front().unwrap();
+
+        ll.sort_by(|a, b| a.0.cmp(&b.0));
+        events.sort_by_key(|e| e.0);
+        assert_eq!(ll.validate(), Ok(()));
+        assert_eq!(ll.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>(), events);
+        assert_eq!(ll[first_ptr].get_data(), Some(&first));
+
+        // still a working deque afterwards
+        ll.push_rear((255, 0));
+        ll.push_front((0, 0));
+        assert_eq!(ll.validate(), Ok(()));
+        assert_eq!(ll.len(), len as usize + 2);
+    }
+
+    let mut packed: PackedLinkedList<u32> = [5, 3, 9, 1, 1, 0].iter().copied().collect();
+    packed.sort();
+    assert_eq!(packed.validate(), Ok(()));
+    assert_eq!(packed.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>(), [0, 1, 1, 3, 5, 9]);
+}
+
+#[test]
+fn reverse_tests() {
+    let items = |ll: &LinkedList<i32>| ll.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>();
+
+    let mut ll: LinkedList<i32> = LinkedList::new();
+    ll.reverse();
+    assert_eq!(ll.validate(), Ok(()));
+    assert!(ll.is_empty());
+
+    ll.push_rear(1);
+    ll.reverse();
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(items(&ll), [1]);
+
+    let mut ll: LinkedList<i32> = (0..100).collect();
+    ll.reverse();
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(items(&ll), (0..100).rev().collect::<Vec<_>>());
+
+    // both ends still work after a reverse
+    ll.push_front(100);
+    ll.push_rear(-1);
+    assert_eq!(ll.pop_rear(), Some(-1));
+    assert_eq!(ll.pop_rear(), Some(0));
+    assert_eq!(ll.pop_front(), Some(100));
+    assert_eq!(ll.pop_front(), Some(99));
+    assert_eq!(ll.validate(), Ok(()));
+    ll.reverse();
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(items(&ll), (1..99).collect::<Vec<_>>());
+
+    let mut packed: PackedLinkedList<u8> = (0..3).collect();
+    packed.reverse();
+    assert_eq!(packed.validate(), Ok(()));
+    assert_eq!(packed.into_iter().collect::<Vec<_>>(), [2, 1, 0]);
+}
+
+#[test]
+fn swap_tests() {
+    for len in 1..7 {
+        for i in 0..len {
+            for j in 0..len {
+                let mut ll: LinkedList<usize> = (0..len).collect();
+                let ptrs = ll.node_index_iter().collect::<Vec<_>>();
+                let mut expected = (0..len).collect::<Vec<_>>();
+
+                ll.swap(ptrs[i], ptrs[j]);
+                expected.swap(i, j);
+                assert_eq!(ll.validate(), Ok(()));
+                assert_eq!(ll.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>(), expected);
+                // the pointers follow their payloads
+                for (k, &ptr) in ptrs.iter().enumerate() {
+                    assert_eq!(ll[ptr].get_data(), Some(&k));
+                }
+            }
+        }
+    }
+
+    let mut packed: PackedLinkedList<u8> = (0..4).collect();
+    let (front, rear) = (packed.front, packed.rear);
+    packed.swap(front, rear);
+    assert_eq!(packed.validate(), Ok(()));
+    assert_eq!((packed.front, packed.rear), (rear, front));
+    assert_eq!(packed.into_iter().collect::<Vec<_>>(), [3, 1, 2, 0]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_tests() {
+    use serde::{
+        de::value::{Error, SeqDeserializer},
+        Deserialize,
+    };
+    use serde_test::{assert_ser_tokens, Token};
+    let deserialize = |items: Vec<i32>| {
+        LinkedList::<i32>::deserialize(SeqDeserializer::<_, Error>::new(items.into_iter())).unwrap()
+    };
+
+    let empty: LinkedList<i32> = LinkedList::new();
+    let tokens = [Token::Seq { len: Some(0) }, Token::SeqEnd];
+    assert_ser_tokens(&empty, &tokens);
+    let rebuilt = deserialize(vec![]);
+    assert!(rebuilt.is_empty());
+    assert_eq!(rebuilt.validate(), Ok(()));
+
+    // fragmented node memory, the wire format only has the sequence
+    let mut ll: LinkedList<i32> = (0..10).collect();
+    ll.retain(|v| v % 3 == 0);
+    ll.push_front(-1);
+    assert_eq!(ll.pool_len(), 5);
+    let tokens = [
+        Token::Seq { len: Some(5) },
+        Token::I32(-1),
+        Token::I32(0),
+        Token::I32(3),
+        Token::I32(6),
+        Token::I32(9),
+        Token::SeqEnd,
+    ];
+    assert_ser_tokens(&ll, &tokens);
+
+    let rebuilt = deserialize(ll.iter().map(|n| *n.get_data().unwrap()).collect());
+    assert_eq!(rebuilt.validate(), Ok(()));
+    assert_eq!(rebuilt.pool_len(), 0);
+    assert_eq!(rebuilt.into_iter().collect::<Vec<_>>(), [-1, 0, 3, 6, 9]);
+
+    let packed: PackedLinkedList<u8> = (1..3).collect();
+    assert_ser_tokens(&packed, &[Token::Seq { len: Some(2) }, Token::U8(1), Token::U8(2), Token::SeqEnd]);
+    let packed = PackedLinkedList::<u8>::deserialize(SeqDeserializer::<_, Error>::new(vec![1, 2].into_iter()));
+    assert_eq!(packed.unwrap().into_iter().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn clone_debug_eq_tests() {
+    let mut a: LinkedList<String> = ["x", "a", "y", "b", "c"].iter().map(|s| s.to_string()).collect();
+    a.retain(|s| s.len() == 1 && s != "x" && s != "y");
+    a.push_rear("d".to_string());
+    let b: LinkedList<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+
+    // same items, different removal histories and memory layouts
+    assert_ne!(a.memory.len(), b.memory.len());
+    assert_eq!(a, b);
+    assert_eq!(format!("{:?}", a), r#"["a", "b", "c", "d"]"#);
+
+    let c = a.clone();
+    assert_eq!(c.validate(), Ok(()));
+    assert_eq!(c, a);
+    assert_eq!(c.pool_len(), 0);
+    assert_eq!(c.memory.len(), 4);
+
+    let mut d = b.clone();
+    d.pop_rear();
+    assert_ne!(d, b);
+    d.push_rear("e".to_string());
+    assert_ne!(d, b);
+
+    let empty: PackedLinkedList<u8> = PackedLinkedList::new();
+    assert_eq!(format!("{:?}", empty), "[]");
+    assert_eq!(empty.clone(), empty);
+    let packed: PackedLinkedList<u8> = (0..3).collect();
+    assert_eq!(format!("{:?}", packed.clone()), "[0, 1, 2]");
+    assert_ne!(packed, empty);
+}
+
+#[test]
+fn positional_access_tests() {
+    let mut ll: LinkedList<usize> = LinkedList::new();
+    assert_eq!(ll.ptr_at(0), None);
+    assert_eq!(ll.get(0), None);
+
+    ll.extend(0..9);
+    let ptrs = ll.node_index_iter().collect::<Vec<_>>();
+    for (i, &ptr) in ptrs.iter().enumerate() {
+        assert_eq!(ll.ptr_at(i), Some(ptr));
+        assert_eq!(ll.get(i), Some(&i));
+    }
+    assert_eq!(ll.ptr_at(9), None);
+    assert_eq!(ll.get(100), None);
+    assert_eq!(ll.get_mut(9), None);
+
+    *ll.get_mut(7).unwrap() = 70;
+    assert_eq!(ll.get(7), Some(&70));
+
+    // index based edits
+    let p = ll.ptr_at(2).unwrap();
+    ll.insert_before(p, 20);
+    let p = ll.ptr_at(0).unwrap();
+    ll.remove(p);
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(ll.into_iter().collect::<Vec<_>>(), [1, 20, 2, 3, 4, 5, 6, 70, 8]);
+
+    let packed: PackedLinkedList<u8> = (0..2).collect();
+    assert_eq!((packed.get(0), packed.get(1), packed.get(2)), (Some(&0), Some(&1), None));
+}
+
+#[test]
+fn validate_tests() {
+    let fresh = || -> LinkedList<i32> {
+        let mut ll: LinkedList<i32> = (0..6).collect();
+        ll.pop_front();
+        ll.pop_rear();
+        ll
+    };
+    // memory: [pooled 0, 1, 2, 3, 4, pooled 5], pool = 5 -> 0
+    let ll = fresh();
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(LinkedList::<i32>::new().validate(), Ok(()));
+
+    let mut ll = fresh();
+    ll.rear = !0;
+    assert_eq!(ll.validate(), Err(ListCorruption::BadEnds { front: 1, rear: !0 }));
+
+    let mut ll = fresh();
+    ll[2].children[1] = 17;
+    assert_eq!(ll.validate(), Err(ListCorruption::Dangling { node: 2, target: 17 }));
+
+    let mut ll = fresh();
+    ll[3].children[0] = 1;
+    assert_eq!(ll.validate(), Err(ListCorruption::PrevMismatch { node: 3 }));
+
+    // a short cycle 1 -> 2 -> 1
+    let mut ll = fresh();
+    ll[2].children[1] = 1;
+    ll[1].children[0] = 2;
+    assert_eq!(ll.validate(), Err(ListCorruption::Revisited { node: 1 }));
+
+    let mut ll = fresh();
+    ll.len = 3;
+    assert_eq!(ll.validate(), Err(ListCorruption::ForwardWalk { node: 3 }));
+
+    let mut ll = fresh();
+    ll.pool = 2;
+    assert_eq!(ll.validate(), Err(ListCorruption::PoolOverlap { node: 2 }));
+
+    let mut ll = fresh();
+    ll.pool = 0;
+    assert_eq!(ll.validate(), Err(ListCorruption::Unreachable { node: 5 }));
+
+    let mut ll = fresh();
+    ll[0].children[0] = 5;
+    assert_eq!(ll.validate(), Err(ListCorruption::Revisited { node: 5 }));
+}
+
+#[test]
+fn move_to_ends_tests() {
+    let mut ll: LinkedList<usize> = (0..5).collect();
+    let ptrs = ll.node_index_iter().collect::<Vec<_>>();
+    let items = |ll: &LinkedList<usize>| {
+        assert_eq!(ll.validate(), Ok(()));
+        ll.iter().map(|n| *n.get_data().unwrap()).collect::<Vec<_>>()
+    };
+
+    ll.move_to_front(ptrs[0]);
+    assert_eq!(items(&ll), [0, 1, 2, 3, 4]);
+    ll.move_to_front(ptrs[2]);
+    assert_eq!(items(&ll), [2, 0, 1, 3, 4]);
+    ll.move_to_front(ptrs[4]);
+    assert_eq!(items(&ll), [4, 2, 0, 1, 3]);
+    ll.move_to_rear(ptrs[4]);
+    assert_eq!(items(&ll), [2, 0, 1, 3, 4]);
+    ll.move_to_rear(ptrs[4]);
+    assert_eq!(items(&ll), [2, 0, 1, 3, 4]);
+    ll.move_to_rear(ptrs[0]);
+    assert_eq!(items(&ll), [2, 1, 3, 4, 0]);
+    // pointers keep their payloads and nothing was allocated
+    for (k, &ptr) in ptrs.iter().enumerate() {
+        assert_eq!(ll[ptr].get_data(), Some(&k));
+    }
+    assert_eq!(ll.memory.len(), 5);
+
+    let mut two: PackedLinkedList<u8> = (0..2).collect();
+    let rear = two.rear;
+    two.move_to_front(rear);
+    assert_eq!(two.validate(), Ok(()));
+    assert_eq!(two.clone().into_iter().collect::<Vec<_>>(), [1, 0]);
+    let front = two.front;
+    two.move_to_rear(front);
+    assert_eq!(two.validate(), Ok(()));
+    assert_eq!(two.into_iter().collect::<Vec<_>>(), [0, 1]);
+
+    let mut one: LinkedList<u8> = std::iter::once(1).collect();
+    let front = one.front;
+    one.move_to_front(front);
+    one.move_to_rear(front);
+    assert_eq!(one.validate(), Ok(()));
+}
+
+#[test]
+fn generational_handle_tests() {
+    let mut ll: LinkedList<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    let b = ll.handle(ll.ptr_at(1).unwrap()).unwrap();
+    assert_eq!(ll.get_by_handle(b).map(|s| s.as_str()), Ok("b"));
+    assert_eq!(ll.handle(!0), None);
+
+    // handles survive relinking since the slot doesn't change
+    ll.move_to_front(b.index());
+    ll.reverse();
+    assert_eq!(ll.resolve(b), Ok(b.index()));
+    ll.get_by_handle_mut(b).unwrap().push('!');
+
+    let d = ll.insert_after_by_handle(b, "d".to_string()).unwrap();
+    let e = ll.insert_before_by_handle(d, "e".to_string()).unwrap();
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(format!("{:?}", ll), r#"["c", "a", "b!", "e", "d"]"#);
+
+    assert_eq!(ll.remove_by_handle(b).as_deref(), Ok("b!"));
+    // removed but not reused yet
+    assert_eq!(ll.resolve(b), Err(StalePointer));
+    assert_eq!(ll.remove_by_handle::<String>(b), Err(RemoveError::NotLinked));
+
+    // the slot gets reused, the old handle must not see the new item
+    ll.push_rear("f".to_string());
+    let f = ll.handle(ll.rear).unwrap();
+    assert_eq!(f.index(), b.index());
+    assert_ne!(f, b);
+    assert_eq!(ll.get_by_handle::<String>(b), Err(StalePointer));
+    assert_eq!(ll.insert_after_by_handle(b, "g".to_string()), Err(StalePointer));
+    assert_eq!(ll.get_by_handle(f).map(|s| s.as_str()), Ok("f"));
+    assert_eq!(ll.get_by_handle(e).map(|s| s.as_str()), Ok("e"));
+
+    let mut packed: PackedLinkedList<u8> = (0..2).collect();
+    let front = packed.handle(packed.front).unwrap();
+    packed.pop_front();
+    packed.push_front(7);
+    assert_eq!(packed.get_by_handle::<u8>(front), Err(StalePointer));
+}
+
+#[test]
+fn compact_tests() {
+    let mut empty: LinkedList<u8> = LinkedList::new();
+    assert_eq!(empty.compact(), []);
+    assert_eq!(empty.validate(), Ok(()));
+
+    let mut ll: LinkedList<String> = (0..20).map(|k| k.to_string()).collect();
+    ll.retain(|s| s.len() == 2);
+    ll.reverse();
+    ll.push_front("x".to_string());
+    ll.swap(ll.ptr_at(1).unwrap(), ll.ptr_at(5).unwrap());
+    let before = format!("{:?}", ll);
+    let handle = ll.handle(ll.front).unwrap();
+    assert!(ll.pool_len() > 0);
+
+    // some outside table holding raw pointers, like the lru key table
+    let mut table = ll
+        .node_index_iter()
+        .map(|ptr| (ll[ptr].get_data().unwrap().clone(), ptr))
+        .collect::<std::collections::HashMap<_, _>>();
+
+    let remap = ll.compact();
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(format!("{:?}", ll), before);
+    assert_eq!(ll.pool_len(), 0);
+    assert_eq!(ll.memory.len(), ll.len());
+    assert_eq!(remap.len(), ll.len());
+    assert!(ll.node_index_iter().eq(0..ll.len() as u32));
+
+    let remap = remap.into_iter().collect::<std::collections::HashMap<_, _>>();
+    for (key, ptr) in table.iter_mut() {
+        *ptr = remap[ptr];
+        assert_eq!(ll[*ptr].get_data(), Some(key));
+    }
+    assert_eq!(ll.resolve(handle), Err(StalePointer));
+
+    // still a working list
+    ll.push_rear("y".to_string());
+    let front = ll.front;
+    ll.remove(front);
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(ll.peek_rear().map(|s| s.as_str()), Some("y"));
+}
+
+#[test]
+fn compact_keeps_generations_of_dropped_slots_test() {
+    let mut ll: LinkedList<u32> = (0..8).collect();
+    let last = ll.handle(7).unwrap();
+    (0..4).for_each(|ptr| {
+        ll.remove(ptr);
+    });
+    ll.compact();
+    assert_eq!(ll.resolve(last), Err(StalePointer));
+
+    // memory grows back past the slot `last` pointed at
+    (8..12).for_each(|k| ll.push_rear(k));
+    assert_eq!(ll.memory.len(), 8);
+    assert_eq!(ll.resolve(last), Err(StalePointer));
+    assert_eq!(ll.validate(), Ok(()));
+
+    // same when the slots come back through append
+    let mut other: LinkedList<u32> = (0..8).collect();
+    let last = other.handle(7).unwrap();
+    (0..4).for_each(|ptr| {
+        other.remove(ptr);
+    });
+    other.compact();
+    let mut tail: LinkedList<u32> = (0..4).collect();
+    other.append(&mut tail);
+    assert_eq!(other.memory.len(), 8);
+    assert_eq!(other.resolve(last), Err(StalePointer));
+    assert_eq!(other.validate(), Ok(()));
+    (0..4).for_each(|k| tail.push_rear(k));
+    assert!(tail.node_index_iter().all(|ptr| tail.handle(ptr).is_some()));
+}
+
+#[test]
+fn try_remove_tests() {
+    let mut ll: LinkedList<char> = "abc".chars().collect();
+    assert_eq!(ll.try_remove(3), Err(RemoveError::OutOfBounds));
+    assert_eq!(ll.try_remove(!0), Err(RemoveError::OutOfBounds));
+    assert_eq!(ll.try_remove(1), Ok('b'));
+    assert_eq!(ll.try_remove(1), Err(RemoveError::NotLinked));
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(format!("{:?}", ll), "['a', 'c']");
+
+    let a = ll.handle(0).unwrap();
+    assert_eq!(ll.remove_by_handle(a), Ok('a'));
+    assert_eq!(ll.remove_by_handle::<char>(a), Err(RemoveError::NotLinked));
+    ll.push_rear('d');
+    assert_eq!(ll.remove_by_handle::<char>(a), Err(RemoveError::StaleGeneration));
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(format!("{:?}", ll), "['c', 'd']");
+
+    let mut empty: PackedLinkedList<u8> = PackedLinkedList::new();
+    assert_eq!(empty.try_remove(0), Err(RemoveError::OutOfBounds));
+    empty.push_rear(1);
+    empty.pop_rear();
+    assert_eq!(empty.try_remove(0), Err(RemoveError::NotLinked));
+}
+
+#[test]
+fn iter_ptr_tests() {
+    let mut ll: LinkedList<i32> = (0..10).collect();
+    ll.pop_front();
+    ll.push_front(-1);
+
+    for (ptr, data) in ll.iter_ptr() {
+        assert_eq!(ll[ptr].get_data(), Some(data));
+    }
+    assert_eq!(ll.iter_ptr().map(|(_, &v)| v).collect::<Vec<_>>(), [-1, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    assert_eq!(ll.iter_ptr().next(), Some((ll.front, &-1)));
+    assert_eq!(ll.iter_ptr().next_back(), Some((ll.rear, &9)));
+
+    // build an index and remove through it
+    let odd = ll.iter_ptr().filter(|(_, &v)| v % 2 != 0).map(|(ptr, _)| ptr).collect::<Vec<_>>();
+    for ptr in odd {
+        ll.remove(ptr);
+    }
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(format!("{:?}", ll), "[2, 4, 6, 8]");
+
+    for (ptr, data) in ll.iter_ptr_mut() {
+        *data += ptr as i32 * 100;
+    }
+    let expected = ll.node_index_iter().map(|ptr| (ptr, ptr as i32 * 100)).collect::<Vec<_>>();
+    let got = ll.iter_ptr().map(|(ptr, &v)| (ptr, v - v % 100)).collect::<Vec<_>>();
+    assert_eq!(got, expected);
+
+    let empty: PackedLinkedList<u8> = PackedLinkedList::new();
+    assert_eq!(empty.iter_ptr().count(), 0);
+}
+
+#[test]
+fn dedup_tests() {
+    let check = |items: &[i32]| {
+        let mut ll: LinkedList<i32> = items.iter().copied().collect();
+        let mut expected = items.to_vec();
+        ll.dedup();
+        expected.dedup();
+        assert_eq!(ll.validate(), Ok(()));
+        assert_eq!(ll.into_iter().collect::<Vec<_>>(), expected);
+    };
+    check(&[]);
+    check(&[1]);
+    check(&[4, 4, 4, 4]);
+    check(&[1, 2, 2, 3, 1, 1]);
+    check(&[1, 2, 3, 3, 3]);
+    check(&[5, 5, 1, 2, 3]);
+
+    // first occurrences are kept and the freed nodes get reused
+    let mut ll: LinkedList<(u8, char)> = vec![(1, 'a'), (1, 'b'), (2, 'c'), (2, 'd'), (2, 'e')].into_iter().collect();
+    let first = ll.front;
+    ll.dedup_by(|a, b| a.0 == b.0);
+    assert_eq!(ll.validate(), Ok(()));
+    assert_eq!(ll.front, first);
+    assert_eq!(format!("{:?}", ll), "[(1, 'a'), (2, 'c')]");
+    assert_eq!(ll.pool_len(), 3);
+
+    // sort + unique
+    let mut packed: PackedLinkedList<u8> = [3, 1, 3, 2, 1, 3].iter().copied().collect();
+    packed.sort();
+    packed.dedup();
+    assert_eq!(packed.validate(), Ok(()));
+    assert_eq!(format!("{:?}", packed), "[1, 2, 3]");
+}
+
+#[test]
+fn iter_from_tests() {
+    let mut ll: LinkedList<i32> = (0..6).collect();
+    ll.pop_front();
+    ll.push_rear(6);
+
+    let three = ll.find(|&v| v == 3).unwrap();
+    assert_eq!(ll.iter_from(three).copied().collect::<Vec<_>>(), [3, 4, 5, 6]);
+    assert_eq!(ll.iter_from_rev(three).copied().collect::<Vec<_>>(), [3, 2, 1]);
+    assert_eq!(ll.iter_from(ll.front).count(), ll.len());
+    assert_eq!(ll.iter_from(ll.rear).copied().collect::<Vec<_>>(), [6]);
+    assert_eq!(ll.iter_from_rev(ll.front).copied().collect::<Vec<_>>(), [1]);
+    assert_eq!(ll.iter_from_rev(ll.rear).count(), ll.len());
+
+    // invalid pointers give nothing
+    assert_eq!(ll.iter_from(!0).count(), 0);
+    assert_eq!(ll.iter_from(100).count(), 0);
+    ll.remove(three);
+    assert_eq!(ll.iter_from(three).count(), 0);
+    assert_eq!(ll.iter_from_rev(three).count(), 0);
+
+    let mut one: PackedLinkedList<u8> = std::iter::once(9).collect();
+    assert_eq!(one.iter_from(one.front).copied().collect::<Vec<_>>(), [9]);
+    let front = one.front;
+    one.pop_front();
+    assert_eq!(one.iter_from(front).count(), 0);
+}
+
+#[test]
+fn position_of_tests() {
+    let mut ll: LinkedList<char> = "abcde".chars().collect();
+    let ptrs = ll.node_index_iter().collect::<Vec<_>>();
+    for (i, &ptr) in ptrs.iter().enumerate() {
+        assert_eq!(ll.position_of(ptr), Some(i));
+        assert_eq!(ll.position_of_from_rear(ptr), Some(4 - i));
+        assert_eq!(ll.ptr_at(i), Some(ptr));
+    }
+    assert_eq!(ll.position_of(!0), None);
+    assert_eq!(ll.position_of_from_rear(5), None);
+
+    let c = ll.handle(ptrs[2]).unwrap();
+    ll.move_to_front(ptrs[2]);
+    assert_eq!(ll.position_of(ptrs[2]), Some(0));
+    assert_eq!(ll.position_of_handle(c), Some(0));
+    assert_eq!(ll.position_of(ptrs[0]), Some(1));
+
+    // removed, then the slot gets reused by another item
+    ll.remove(ptrs[2]);
+    assert_eq!(ll.position_of(ptrs[2]), None);
+    assert_eq!(ll.position_of_handle(c), None);
+    ll.push_rear('z');
+    assert_eq!(ll.position_of(ptrs[2]), Some(4));
+    assert_eq!(ll.position_of_handle(c), None);
+}
+
+#[test]
+fn bytes_round_trip_tests() {
+    let encode = |v: &u32, out: &mut [u8]| out.copy_from_slice(&v.to_le_bytes());
+    let decode = |bytes: &[u8]| Some(u32::from_le_bytes(bytes.try_into().ok()?));
+
+    let empty: LinkedList<u32> = LinkedList::new();
+    let bytes = empty.to_bytes_with(4, encode);
+    assert_eq!(bytes, [0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0]);
+    assert_eq!(LinkedList::from_bytes_with(&bytes, 4, decode), Ok(empty));
+
+    // fragmented memory, only the traversal order is stored
+    let mut ll: LinkedList<u32> = (0..50).collect();
+    ll.retain(|v| v % 7 != 0);
+    ll.reverse();
+    let bytes = ll.to_bytes_with(4, encode);
+    assert_eq!(bytes.len(), 12 + 4 * ll.len());
+    assert_eq!(&bytes[12..16], &48u32.to_le_bytes());
+    let rebuilt = LinkedList::from_bytes_with(&bytes, 4, decode).unwrap();
+    assert_eq!(rebuilt.validate(), Ok(()));
+    assert_eq!(rebuilt.pool_len(), 0);
+    assert_eq!(rebuilt, ll);
+
+    let packed: PackedLinkedList<u32> = PackedLinkedList::from_bytes_with(&bytes, 4, decode).unwrap();
+    assert!(packed.data_iter().eq(ll.data_iter()));
+
+    // every truncation is an error, not a panic
+    for len in 0..bytes.len() {
+        let res = LinkedList::from_bytes_with(&bytes[..len], 4, decode);
+        assert_eq!(res.err(), Some(DecodeError::Truncated), "len {}", len);
+    }
+    let mut long = bytes.clone();
+    long.push(0);
+    assert_eq!(LinkedList::from_bytes_with(&long, 4, decode).err(), Some(DecodeError::TrailingBytes));
+    assert_eq!(
+        LinkedList::from_bytes_with(&bytes, 8, |_| Some(0u32)).err(),
+        Some(DecodeError::ElementSize { expected: 8, found: 4 })
+    );
+
+    // corrupted headers and payloads
+    let mut state: u32 = 13;
+    for _ in 0..500 {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        let mut corrupt = bytes.clone();
+        let at = (state >> 8) as usize % corrupt.len();
+        corrupt[at] ^= (state >> 24) as u8 | 1;
+        let picky = |bytes: &[u8]| decode(bytes).filter(|&v| v < 50);
+        match LinkedList::from_bytes_with(&corrupt, 4, picky) {
+            Ok(list) => assert_eq!(list.validate(), Ok(())),
+            Err(err) => assert!(at >= 12 || !matches!(err, DecodeError::InvalidElement { .. })),
+        }
+    }
+    let mut huge = bytes.clone();
+    huge[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(LinkedList::from_bytes_with(&huge, 4, decode).err(), Some(DecodeError::TooLong));
+    huge[..8].copy_from_slice(&(u32::MAX as u64 - 1).to_le_bytes());
+    assert_eq!(LinkedList::from_bytes_with(&huge, 4, decode).err(), Some(DecodeError::Truncated));
+    assert_eq!(
+        LinkedList::<u32>::from_bytes_with(&bytes, 4, |b| decode(b).filter(|&v| v != 47)).err(),
+        Some(DecodeError::InvalidElement { index: 1 })
+    );
+
+    // zero sized payloads can't back a count, even with a decoder that takes anything
+    let empty: LinkedList<()> = LinkedList::new();
+    let bytes = empty.to_bytes_with(0, |_, _| {});
+    assert_eq!(LinkedList::from_bytes_with(&bytes, 0, |_| Some(())), Ok(empty));
+    let units: LinkedList<()> = (0..3).map(|_| ()).collect();
+    let mut huge = units.to_bytes_with(0, |_, _| {});
+    assert_eq!(huge.len(), 12);
+    assert_eq!(
+        LinkedList::from_bytes_with(&huge, 0, |_| Some(())).err(),
+        Some(DecodeError::ZeroSizedElements)
+    );
+    huge[..8].copy_from_slice(&(u32::MAX as u64 - 1).to_le_bytes());
+    assert_eq!(
+        LinkedList::from_bytes_with(&huge, 0, |_| Some(())).err(),
+        Some(DecodeError::ZeroSizedElements)
+    );
+}
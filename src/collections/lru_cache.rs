@@ -1,123 +1,2495 @@
 use super::linked_list::*;
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    borrow::Borrow,
+    cell::Cell,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::FromIterator,
+    sync::{Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
 
 /// # Description
 /// A generic LRU cache implemented using a hashtable and doubly-linked-list
 /// # Comments
-/// The implementation clones keys very liberally, so keys should be `Copy` but
-/// if that's not possible try to use `Clone`-friendly keys
+/// Every key lives exactly once, in its list node. The hashtable only stores node pointers so
+/// keys are never cloned. \
+/// The cache is `Send` (given `K` and `V` are) but not `Sync` since lookups through `&self` still bump the hit/miss
+/// counters
 pub struct LruCache<K, V> {
-    key_table: HashMap<K, u32>,
+    key_table: KeyIndex,
     list: LinkedList<(K, V)>,
     cache_size: usize,
+    stats: CacheStats,
+    on_evict: Option<EvictionHandler<K, V>>,
+    /// bookkeeping for every node, indexed by node pointer
+    meta: Vec<NodeMeta>,
+    clock: Option<Clock>,
+    weigher: Option<Weigher<K, V>>,
+    /// sum of the weights of every cached entry, just `len()` without a weigher
+    total_weight: usize,
+}
+
+#[derive(Copy, Clone, Default)]
+struct NodeMeta {
+    /// `None` means the entry never expires
+    deadline: Option<Instant>,
+    /// what the weigher said when the value was put, 1 without a weigher
+    weight: usize,
+}
+
+/// # Description
+/// Open addressing hashtable of node pointers, the keys it's indexed by live in the list nodes
+/// # Comments
+/// Linear probing with backward shift deletion so there are no tombstones. Every slot keeps the
+/// full hash next to the pointer, growing never has to look at a key again and most mismatches
+/// are caught without touching the list. \
+/// The table doubles before it gets more than 3/4 full, past that linear probing clusters badly
+/// and misses (the common case for a cache) end up scanning long runs
+struct KeyIndex {
+    /// `(hash, node pointer)`, the length is always 0 or a power of two
+    slots: Vec<(u64, u32)>,
+    len: usize,
+    hasher: RandomState,
+}
+
+/// marks a free slot in `KeyIndex`
+const EMPTY: u32 = !0;
+
+impl KeyIndex {
+    fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            len: 0,
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn hash<Q: Hash + ?Sized>(&self, key: &Q) -> u64 {
+        self.hasher.hash_one(key)
+    }
+
+    /// the first pointer stored under `hash` that `is_match` accepts
+    fn find<F: Fn(u32) -> bool>(&self, hash: u64, is_match: F) -> Option<u32> {
+        if self.len == 0 {
+            return None;
+        }
+        let mask = self.slots.len() - 1;
+        let mut idx = hash as usize & mask;
+        // the load factor stays under 1 so the probe always hits an empty slot eventually
+        loop {
+            let (slot_hash, node_ptr) = self.slots[idx];
+            if node_ptr == EMPTY {
+                return None;
+            }
+            if slot_hash == hash && is_match(node_ptr) {
+                return Some(node_ptr);
+            }
+            idx = (idx + 1) & mask;
+        }
+    }
+
+    /// indexes `node_ptr` under `hash`, the caller makes sure its key isn't in the table yet
+    fn insert(&mut self, hash: u64, node_ptr: u32) {
+        if (self.len + 1) * 4 > self.slots.len() * 3 {
+            let capacity = (self.slots.len() * 2).max(8);
+            let old_slots = std::mem::replace(&mut self.slots, vec![(0, EMPTY); capacity]);
+            for (slot_hash, old_ptr) in old_slots.into_iter().filter(|&(_, p)| p != EMPTY) {
+                self.place(slot_hash, old_ptr);
+            }
+        }
+        self.place(hash, node_ptr);
+        self.len += 1;
+    }
+
+    fn place(&mut self, hash: u64, node_ptr: u32) {
+        let mask = self.slots.len() - 1;
+        let mut idx = hash as usize & mask;
+        while self.slots[idx].1 != EMPTY {
+            idx = (idx + 1) & mask;
+        }
+        self.slots[idx] = (hash, node_ptr);
+    }
+
+    /// drops `node_ptr` which has to be indexed under `hash`
+    fn remove(&mut self, hash: u64, node_ptr: u32) {
+        let mask = self.slots.len() - 1;
+        let mut hole = hash as usize & mask;
+        while self.slots[hole].1 != node_ptr {
+            assert_ne!(self.slots[hole].1, EMPTY, "node pointer should be indexed");
+            hole = (hole + 1) & mask;
+        }
+
+        // shift the rest of the cluster back so every entry stays reachable from its home slot
+        let mut next = (hole + 1) & mask;
+        while self.slots[next].1 != EMPTY {
+            let home = self.slots[next].0 as usize & mask;
+            // the entry can fill the hole unless its home sits between the hole and itself
+            if next.wrapping_sub(home) & mask >= next.wrapping_sub(hole) & mask {
+                self.slots[hole] = self.slots[next];
+                hole = next;
+            }
+            next = (next + 1) & mask;
+        }
+        self.slots[hole] = (0, EMPTY);
+        self.len -= 1;
+    }
+
+    /// empties the table but keeps its memory
+    fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = (0, EMPTY));
+        self.len = 0;
+    }
+}
+
+/// called with every pair the cache pushes out to make room
+type EvictionHandler<K, V> = Box<dyn FnMut(K, V) + Send + Sync>;
+
+/// where the cache gets the current time from, `Instant::now` when not set
+type Clock = Box<dyn Fn() -> Instant + Send + Sync>;
+
+/// the cost of an entry in a weighted cache
+type Weigher<K, V> = Box<dyn Fn(&K, &V) -> usize + Send + Sync>;
+
+/// # Description
+/// Counters kept by every `LruCache`
+/// # Comments
+/// `peek(..)` only borrows the cache so the lookup counters are `Cell`s, a plain increment on
+/// every lookup. That makes `LruCache` `!Sync`, share it behind a `Mutex` (or use
+/// `ConcurrentLruCache`) across threads
+#[derive(Default)]
+struct CacheStats {
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+    evictions: u64,
+    insertions: u64,
+}
+
+impl CacheStats {
+    fn record_lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.set(counter.get() + 1);
+    }
+
+    fn hits(&self) -> u64 {
+        self.hits.get()
+    }
+
+    fn misses(&self) -> u64 {
+        self.misses.get()
+    }
+
+    fn hit_rate(&self) -> f64 {
+        hit_rate(self.hits(), self.misses())
+    }
+}
+
+/// `hits / (hits + misses)`, 0.0 when there were no lookups
+fn hit_rate(hits: u64, misses: u64) -> f64 {
+    if hits + misses == 0 {
+        return 0.0;
+    }
+    hits as f64 / (hits + misses) as f64
 }
 
 impl<K, V> LruCache<K, V>
 where
-    K: Clone + Eq + Hash,
+    K: Eq + Hash,
 {
     /// # Description
     /// Creates a new LruCache of size `cache_size`
     /// # Comments
-    /// No allication takes place here
+    /// No allication takes place here. A size of 0 is allowed, that cache never holds anything:
+    /// `put` hands the pair back (or to the eviction handler) and every lookup misses
     pub fn new(cache_size: usize) -> Self {
         Self {
-            key_table: HashMap::new(),
+            key_table: KeyIndex::new(),
             list: LinkedList::new(),
             cache_size,
+            stats: CacheStats::default(),
+            on_evict: None,
+            meta: Vec::new(),
+            clock: None,
+            weigher: None,
+            total_weight: 0,
+        }
+    }
+
+    /// # Description
+    /// Creates a weighted LruCache, entries are evicted from the LRU end until the weights of
+    /// everything cached add up to no more than `max_weight`
+    /// # Comments
+    /// `capacity()` is `max_weight` here. An entry is weighed once when it's put, so changing a
+    /// value through `get`/`peek_mut`/`entry` doesn't re-weigh it, `put` it again for that. \
+    /// A single pair heavier than `max_weight` is never cached, `put` hands it back the same
+    /// way a cache of size 0 does
+    pub fn with_weigher<F>(max_weight: usize, weigher: F) -> Self
+    where
+        F: Fn(&K, &V) -> usize + Send + Sync + 'static,
+    {
+        let mut cache = Self::new(max_weight);
+        cache.weigher = Some(Box::new(weigher));
+        cache
+    }
+
+    /// # Description
+    /// Creates a new LruCache of size `cache_size` and puts every pair of `iter` in order
+    /// # Comments
+    /// Later pairs end up more recent, so when `iter` is longer than `cache_size` only its last
+    /// `cache_size` pairs (by distinct key) survive, with the last one as the MRU
+    pub fn from_iter_with_capacity<I>(cache_size: usize, iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut cache = Self::new(cache_size);
+        cache.extend(iter);
+        cache
+    }
+
+    /// # Description
+    /// the total weight of the cached entries, same as `len()` when there's no weigher
+    pub fn current_weight(&self) -> usize {
+        self.total_weight
+    }
+
+    fn weigh(&self, key: &K, val: &V) -> usize {
+        self.weigher.as_ref().map_or(1, |weigher| weigher(key, val))
+    }
+
+    /// # Description
+    /// replaces `Instant::now` as the time source used for TTLs
+    /// # Comments
+    /// Mostly useful for tests, so entries can expire without sleeping
+    pub fn with_clock<F>(mut self, clock: F) -> Self
+    where
+        F: Fn() -> Instant + Send + Sync + 'static,
+    {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    fn now(&self) -> Instant {
+        self.clock.as_ref().map_or_else(Instant::now, |clock| clock())
+    }
+
+    /// true when the entry at `node_ptr` has a TTL that ran out, the clock is only read for
+    /// entries that have one
+    fn is_expired(&self, node_ptr: u32) -> bool {
+        match self.meta[node_ptr as usize].deadline {
+            Some(deadline) => deadline <= self.now(),
+            None => false,
         }
     }
+
+    fn meta_mut(&mut self, node_ptr: u32) -> &mut NodeMeta {
+        let idx = node_ptr as usize;
+        if idx >= self.meta.len() {
+            self.meta.resize(idx + 1, NodeMeta::default());
+        }
+        &mut self.meta[idx]
+    }
+
+    /// # Description
+    /// Creates a new LruCache of size `cache_size` that hands every evicted pair to `on_evict`
+    /// # Comments
+    /// Only capacity evictions reach the handler: `put`/`entry` on a full cache, `resize` and `put`
+    /// on a cache of size 0. Since the handler owns those pairs, `put` returns `None` and `resize`
+    /// an empty `Vec` instead. `remove`, `pop_lru`, `pop_mru` and `clear` are explicit so the
+    /// pairs go back to the caller (or get dropped) like before. \
+    /// The handler runs after the cache has finished updating itself
+    pub fn with_eviction_handler<F>(cache_size: usize, on_evict: F) -> Self
+    where
+        F: FnMut(K, V) + Send + Sync + 'static,
+    {
+        let mut cache = Self::new(cache_size);
+        cache.on_evict = Some(Box::new(on_evict));
+        cache
+    }
     
+    /// # Description
+    /// number of entries currently cached
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// # Description
+    /// the most entries the cache holds before it starts evicting (`cache_size`)
+    pub fn capacity(&self) -> usize {
+        self.cache_size
+    }
+
+    /// # Description
+    /// removes every entry
+    /// # Comments
+    /// The list nodes go back to its pool and the hashtable keeps its capacity, so refilling
+    /// the cache doesn't allocate. Use `clear_shrink()` to release the memory instead
+    pub fn clear(&mut self) {
+        self.key_table.clear();
+        self.list.drain().for_each(drop);
+        self.total_weight = 0;
+    }
+
+    /// # Description
+    /// same as `clear()` but the memory is released too
+    pub fn clear_shrink(&mut self) {
+        self.key_table = KeyIndex::new();
+        self.list = LinkedList::new();
+        self.meta = Vec::new();
+        self.total_weight = 0;
+    }
+
+    /// # Description
+    /// changes `cache_size` at runtime
+    /// # Returns
+    /// the pairs evicted to fit the new size, least recently used first. Empty when there's an
+    /// eviction handler, it gets them instead
+    /// # Comments
+    /// Growing only raises the limit. Resizing to 0 evicts everything and turns `put` into a no-op
+    /// that hands the pair back
+    pub fn resize(&mut self, new_size: usize) -> Vec<(K, V)> {
+        self.cache_size = new_size;
+        let mut evicted = Vec::new();
+        while self.total_weight > new_size {
+            evicted.push(self.pop_lru().expect("cache isn't empty"));
+        }
+        self.stats.evictions += evicted.len() as u64;
+        match &mut self.on_evict {
+            Some(on_evict) => {
+                evicted.into_iter().for_each(|(k, v)| on_evict(k, v));
+                Vec::new()
+            }
+            None => evicted,
+        }
+    }
+
     /// # Description
     /// Puts a `key`-`value` pair into the `LruCache`
     /// # Comments
     /// If there isn't enough space, the Least Recently Used
-    /// Key-Value pair gets removed
-    pub fn put(&mut self, key: K, val: V) {
-        if self.list.len() < self.cache_size {
-            //cache isnt full so just push front
-            self.list.push_front((key.clone(), val));
-            let node_ptr = self.list.front;
-            self.key_table.insert(key, node_ptr);
-        } else {
-            //cache full
-            match self.key_table.get(&key) {
-                // key exists  ( move existing node to front, update hashtable )
-                Some(&cur_node) => {
-                    let (rkey, _rval) = self.list.remove(cur_node).unwrap();
-                    // push to the top with updated value
-                    self.list.push_front((rkey.clone(), val));
-                    let new_ptr = self.list.front;
-                    *self.key_table.get_mut(&rkey).expect("Key should exist") = new_ptr;
-                }
-                // key doesnt exist ( remove LRU, push new val front, update hashtable)
-                None => {
-                    let (rkey, _rval) = self.list.pop_rear().unwrap();
-                    self.key_table.remove(&rkey).expect("Key should exist");
-                    self.list.push_front((key.clone(), val));
-                    let new_node = self.list.front;
-                    self.key_table.insert(key, new_node);
-                }
+    /// Key-Value pair gets removed. A weighted cache keeps evicting until the new pair fits,
+    /// updating a key with a heavier value can evict too
+    /// # Returns
+    /// the evicted pair, `None` when nothing was evicted (including when `key` was already cached
+    /// and only its value got updated). When a weighted cache evicts several pairs only the
+    /// coldest one is returned, set an eviction handler to see all of them. \
+    /// A cache of size 0 holds nothing so the offered pair comes straight back, and so does a pair
+    /// heavier than the whole cache (any older value for `key` is removed). With an eviction
+    /// handler the pair goes to it and `None` is returned
+    pub fn put(&mut self, key: K, val: V) -> Option<(K, V)> {
+        self.put_with_deadline(key, val, None)
+    }
+
+    /// # Description
+    /// same as `put(..)` but the entry expires once `ttl` has passed, no matter how recently it
+    /// was used
+    /// # Comments
+    /// Expired entries are misses for `get`/`peek`/`entry` and `contains_key` says no, but they
+    /// keep counting towards `len()` and showing up in the iterators until something removes
+    /// them: a lookup through `&mut self`, `purge_expired(..)` or plain LRU eviction.
+    /// Expiring isn't an eviction, so the eviction handler never sees these pairs. \
+    /// A `ttl` too long for `Instant` to represent means the entry never expires
+    pub fn put_with_ttl(&mut self, key: K, val: V, ttl: Duration) -> Option<(K, V)> {
+        let deadline = self.now().checked_add(ttl);
+        self.put_with_deadline(key, val, deadline)
+    }
+
+    fn put_with_deadline(&mut self, key: K, val: V, deadline: Option<Instant>) -> Option<(K, V)> {
+        if self.cache_size == 0 {
+            return self.hand_off(Some((key, val)));
+        }
+
+        let weight = self.weigh(&key, &val);
+        if weight > self.cache_size {
+            // can't fit, and keeping the old value around would be stale
+            self.remove(&key);
+            return self.hand_off(Some((key, val)));
+        }
+
+        // key exists  ( update value in place and move node to front, the pointer stays the same )
+        // this has to be checked whether the cache is full or not, otherwise the key gets a second node
+        if let Some(cur_node) = self.find(&key) {
+            self.list[cur_node].get_data_mut().expect("val should exist").1 = val;
+            self.list.move_to_front(cur_node);
+            let meta = self.meta_mut(cur_node);
+            let old_weight = std::mem::replace(&mut meta.weight, weight);
+            meta.deadline = deadline;
+            self.total_weight = self.total_weight - old_weight + weight;
+            return self.evict_overflow();
+        }
+
+        let new_node = self.insert_new(key, val, weight);
+        self.meta_mut(new_node).deadline = deadline;
+        self.evict_overflow()
+    }
+
+    /// evicts from the LRU end until the cache fits in `cache_size` again \
+    /// With an eviction handler every evicted pair goes to it, coldest first, otherwise the coldest
+    /// one is returned and the rest get dropped
+    fn evict_overflow(&mut self) -> Option<(K, V)> {
+        let mut evicted = Vec::new();
+        while self.total_weight > self.cache_size {
+            evicted.push(self.unlink(self.list.rear));
+        }
+        self.stats.evictions += evicted.len() as u64;
+
+        // the handler only runs once the cache is back within budget, same as `resize`
+        match &mut self.on_evict {
+            Some(on_evict) => {
+                evicted.into_iter().for_each(|(k, v)| on_evict(k, v));
+                None
+            }
+            None => evicted.into_iter().next(),
+        }
+    }
+
+    /// removes the node at `node_ptr` from both the list and the hashtable
+    fn unlink(&mut self, node_ptr: u32) -> (K, V) {
+        let (key, val) = self.list.remove(node_ptr).expect("val should exist");
+        self.key_table.remove(self.key_table.hash(&key), node_ptr);
+        self.total_weight -= self.meta[node_ptr as usize].weight;
+        (key, val)
+    }
+
+    /// passes an evicted pair to the eviction handler if there is one, otherwise it's given back
+    fn hand_off(&mut self, evicted: Option<(K, V)>) -> Option<(K, V)> {
+        match (&mut self.on_evict, evicted) {
+            (Some(on_evict), Some((k, v))) => {
+                on_evict(k, v);
+                None
+            }
+            (_, evicted) => evicted,
+        }
+    }
+
+    /// pushes a key that isn't cached yet to the front and returns its node \
+    /// the cache can be over budget afterwards, `evict_overflow()` fixes that
+    fn insert_new(&mut self, key: K, val: V, weight: usize) -> u32 {
+        self.stats.insertions += 1;
+
+        // push new val front, update hashtable
+        let hash = self.key_table.hash(&key);
+        self.list.push_front((key, val));
+        let new_node = self.list.front;
+        self.key_table.insert(hash, new_node);
+        *self.meta_mut(new_node) = NodeMeta {
+            deadline: None,
+            weight,
+        };
+        self.total_weight += weight;
+        new_node
+    }
+
+    /// # Description
+    /// gets the entry for `key` for in-place manipulation, like `HashMap::entry`
+    /// # Comments
+    /// An occupied entry is promoted to MRU right here, the same as a `get(..)` hit.
+    /// Inserting through a vacant entry respects `cache_size` and evicts the LRU pair when full
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let node_ptr = self.lookup(&key);
+        if node_ptr.is_none() {
+            self.remove_expired(&key);
+        }
+        match node_ptr {
+            Some(node_ptr) => {
+                self.list.move_to_front(node_ptr);
+                Entry::Occupied(OccupiedEntry {
+                    cache: self,
+                    node_ptr,
+                })
             }
+            None => Entry::Vacant(VacantEntry { cache: self, key }),
         }
     }
 
     /// # Description
     /// fetches value associated with `key`, once called
     /// value priority gets upgraded
-    pub fn get(&mut self, key: &K) -> Option<&mut V> {
-        let key_table = &mut self.key_table;
-        let list = &mut self.list;
-        let &node_ptr = key_table.get(&key)?;
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.lookup_or_expire(key)?;
+        // relinking keeps the pointer valid so the key table doesn't change
+        self.list.move_to_front(node_ptr);
+
+        // return newly prioritized node
+        self.list[node_ptr].get_data_mut().map(|(_, v)| v)
+    }
+
+    /// # Description
+    /// promotes `key` to MRU like `get(..)` does, without handing out the value
+    /// # Returns
+    /// true when `key` was cached. Counts as a hit or a miss the same as `get`
+    pub fn touch<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.lookup_or_expire(key) {
+            Some(node_ptr) => {
+                self.list.move_to_front(node_ptr);
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// the node holding `key`, expired or not
+    fn find<Q>(&self, key: &Q) -> Option<u32>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let list = &self.list;
+        self.key_table.find(self.key_table.hash(key), |node_ptr| {
+            list[node_ptr].get_data().is_some_and(|(k, _)| k.borrow() == key)
+        })
+    }
 
-        let (rkey, rval) = list.remove(node_ptr).expect("val should exist");
+    /// hashtable lookup that counts towards the hit/miss stats, expired entries are misses
+    fn lookup<Q>(&self, key: &Q) -> Option<u32>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.find(key).filter(|&node_ptr| !self.is_expired(node_ptr));
+        self.stats.record_lookup(node_ptr.is_some());
+        node_ptr
+    }
 
-        list.push_front((rkey.clone(), rval));
-        let new_node = list.front;
+    /// same as `lookup(..)` but a miss on an expired entry removes it
+    fn lookup_or_expire<Q>(&mut self, key: &Q) -> Option<u32>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.lookup(key);
+        if node_ptr.is_none() {
+            self.remove_expired(key);
+        }
+        node_ptr
+    }
 
-        *key_table.get_mut(&key).expect("key should_exist") = new_node;
+    /// removes `key` if it's cached but expired, only called after a miss so live keys pay nothing
+    fn remove_expired<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if let Some(node_ptr) = self.find(key) {
+            if self.is_expired(node_ptr) {
+                self.remove(key);
+            }
+        }
+    }
 
-        // return newly prioritized node
-        list[new_node].get_data_mut().map(|(_, v)| v)
+    /// # Description
+    /// removes every entry whose TTL ran out at `now`
+    /// # Returns
+    /// how many entries were removed
+    pub fn purge_expired(&mut self, now: Instant) -> usize {
+        let meta = &self.meta;
+        let expired = self
+            .list
+            .node_index_iter()
+            .filter(|&node_ptr| meta[node_ptr as usize].deadline.is_some_and(|deadline| deadline <= now))
+            .collect::<Vec<_>>();
+        for &node_ptr in &expired {
+            self.unlink(node_ptr);
+        }
+        expired.len()
     }
-    
+
+    /// # Description
+    /// number of `get`/`peek`/`entry`/`touch` lookups that found their key
+    pub fn hits(&self) -> u64 {
+        self.stats.hits()
+    }
+
+    /// # Description
+    /// number of `get`/`peek`/`entry`/`touch` lookups that came up empty
+    pub fn misses(&self) -> u64 {
+        self.stats.misses()
+    }
+
+    /// # Description
+    /// number of pairs pushed out to make room, by `put`/`entry` on a full cache or by `resize`
+    /// # Comments
+    /// `remove`, `pop_lru` and `clear` are explicit so they don't count
+    pub fn evictions(&self) -> u64 {
+        self.stats.evictions
+    }
+
+    /// # Description
+    /// number of new keys inserted, updating the value of a cached key doesn't count
+    pub fn insertions(&self) -> u64 {
+        self.stats.insertions
+    }
+
+    /// # Description
+    /// `hits / (hits + misses)`, 0.0 before the first lookup
+    pub fn hit_rate(&self) -> f64 {
+        self.stats.hit_rate()
+    }
+
+    /// # Description
+    /// zeroes every counter, the cached entries are untouched
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// # Description
+    /// returns true when `key` is cached and hasn't expired, priority doesn't change
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some_and(|node_ptr| !self.is_expired(node_ptr))
+    }
+
+    /// # Description
+    /// fetches value associated with `key` without upgrading its priority
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.lookup(key)?;
+        self.list[node_ptr].get_data().map(|(_, v)| v)
+    }
+
+    /// # Description
+    /// same as `peek(..)` but the value can be modified, priority still doesn't change
+    pub fn peek_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.lookup_or_expire(key)?;
+        self.list[node_ptr].get_data_mut().map(|(_, v)| v)
+    }
+
+    /// # Description
+    /// removes `key` from the cache and returns its value, the node goes back to the list's pool
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, val)| val)
+    }
+
+    /// # Description
+    /// keeps only the pairs `f` returns true for, survivors keep their recency order
+    /// # Returns
+    /// how many pairs were removed
+    /// # Comments
+    /// Walks the list once from MRU to LRU. Removed pairs don't count as evictions and don't go
+    /// through the eviction handler
+    pub fn retain<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut removed = 0;
+        let mut node_ptr = self.list.front;
+        for _ in 0..self.list.len() {
+            // grab the link first, unlinking sends the node back to the pool
+            let next = self.list[node_ptr].get_children()[1];
+            let (key, val) = self.list[node_ptr].get_data_mut().expect("val should exist");
+            if !f(key, val) {
+                self.unlink(node_ptr);
+                removed += 1;
+            }
+            node_ptr = next;
+        }
+        removed
+    }
+
+    /// same as `remove(..)` but the owned key comes back too
+    fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.find(key)?;
+        Some(self.unlink(node_ptr))
+    }
+
+    /// # Description
+    /// evicts the Least Recently Used pair and returns it, `None` when the cache is empty
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.unlink(self.list.rear))
+    }
+
+    /// # Description
+    /// the Least Recently Used pair, the next one `put` would evict. Nothing gets promoted
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.iter().next_back().map(|(k, v)| (k, v))
+    }
+
+    /// # Description
+    /// the Most Recently Used pair, nothing gets promoted
+    pub fn peek_mru(&self) -> Option<(&K, &V)> {
+        self.iter().next().map(|(k, v)| (k, v))
+    }
+
+    /// # Description
+    /// removes the Most Recently Used pair and returns it, `None` when the cache is empty
+    pub fn pop_mru(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.unlink(self.list.front))
+    }
+
     /// # Description 
     /// An iterator that walks through all items in the cache
-    pub fn iter(&self) -> impl Iterator<Item = &(K, V)> {
+    /// from MRU to LRU, `.rev()` or `iter_lru_first()` go the other way
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &(K, V)> + ExactSizeIterator {
         self.list.iter().map(|node| node.get_data().unwrap())
     }
-}
 
-#[test]
-fn simple_test(){
-    let to_vec = |c:&LruCache<String,i32>| -> Vec<_>{
-        c.iter().map(|(k,_v)| k.clone() ).collect()
-    };
+    /// # Description
+    /// walks the cache coldest first, the order entries would be evicted in
+    pub fn iter_lru_first(&self) -> impl DoubleEndedIterator<Item = &(K, V)> + ExactSizeIterator {
+        self.iter().rev()
+    }
 
-    let tostr = |a|{
-        String::from(a)
-    };
+    /// # Description
+    /// the keys from MRU to LRU
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.list.iter().map(|node| &node.get_data().unwrap().0)
+    }
 
-    let mut lru = LruCache::<String, i32>::new(4);
+    /// # Description
+    /// the values from MRU to LRU
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.list.iter().map(|node| &node.get_data().unwrap().1)
+    }
 
-    lru.put(String::from("a"), 1);
-    assert_eq!( to_vec(&lru) , [tostr("a")] );
+    /// # Description
+    /// the values from MRU to LRU, recency doesn't change
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut V> + ExactSizeIterator {
+        self.list.iter_mut().map(|node| &mut node.get_data_mut().unwrap().1)
+    }
 
-    lru.put(String::from("b"), 1);
-    assert_eq!( to_vec(&lru) , [tostr("b"),tostr("a")] );
+    /// # Description
+    /// Walks MRU to LRU with mutable values, recency doesn't change.
+    /// Keys stay immutable since the hashtable is indexed by them
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&K, &mut V)> + ExactSizeIterator {
+        self.list.iter_mut().map(|node| {
+            let (k, v) = node.get_data_mut().unwrap();
+            (&*k, v)
+        })
+    }
+}
 
-    lru.put(String::from("c"), 1);
-    assert_eq!( to_vec(&lru) , [tostr("c"),tostr("b"),tostr("a")] );
+impl<K, V> Extend<(K, V)> for LruCache<K, V>
+where
+    K: Eq + Hash,
+{
+    /// puts every pair in order, evicting as needed. Evicted pairs go to the eviction handler or
+    /// get dropped
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, val) in iter {
+            self.put(key, val);
+        }
+    }
+}
 
-    lru.put(String::from("d"), 1);
-    assert_eq!( to_vec(&lru) , [tostr("d"),tostr("c"),tostr("b"),tostr("a")] );
+/// # Description
+/// Consuming iterator returned by `LruCache::into_iter()`, yields the pairs from MRU to LRU
+pub struct LruIntoIter<K, V> {
+    pairs: DLLIntoIter<OptionNode<(K, V)>, (K, V)>,
+}
 
-    lru.put(String::from("e"), 1);
-    assert_eq!( to_vec(&lru) , [tostr("e"),tostr("d"),tostr("c"),tostr("b")]);
+impl<K, V> Iterator for LruIntoIter<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pairs.next()
+    }
 
-    lru.get(&String::from("b"));
-    assert_eq!( to_vec(&lru) , [tostr("b"),tostr("e"),tostr("d"),tostr("c")]);
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pairs.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for LruIntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.pairs.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for LruIntoIter<K, V> {}
+
+impl<K, V> IntoIterator for LruCache<K, V> {
+    type Item = (K, V);
+    type IntoIter = LruIntoIter<K, V>;
+    /// the hashtable is dropped right away, the pairs come out of the list one at a time
+    fn into_iter(self) -> Self::IntoIter {
+        LruIntoIter {
+            pairs: self.list.into_iter(),
+        }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for LruCache<K, V>
+where
+    K: Eq + Hash,
+{
+    /// the cache gets just enough room for every pair, use `from_iter_with_capacity(..)` to
+    /// pick the size
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let pairs = iter.into_iter().collect::<Vec<_>>();
+        Self::from_iter_with_capacity(pairs.len(), pairs)
+    }
+}
+
+/// how many entries `{:?}` prints before cutting the dump short, `{:#?}` prints all of them
+const DEBUG_ENTRIES: usize = 32;
+
+impl<K, V> fmt::Debug for LruCache<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    /// # Description
+    /// Prints the capacity, the length and the entries from MRU to LRU as a map
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Entries<'a, K, V>(&'a LinkedList<(K, V)>, usize);
+
+        impl<'a, K: fmt::Debug, V: fmt::Debug> fmt::Debug for Entries<'a, K, V> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let pairs = self.0.iter().filter_map(|node| node.get_data());
+                f.debug_map()
+                    .entries(pairs.take(self.1).map(|(k, v)| (k, v)))
+                    .finish()
+            }
+        }
+
+        let shown = if f.alternate() { self.list.len() } else { DEBUG_ENTRIES };
+        let mut dbg = f.debug_struct("LruCache");
+        dbg.field("capacity", &self.cache_size)
+            .field("len", &self.list.len())
+            .field("entries", &Entries(&self.list, shown));
+        if self.list.len() > shown {
+            dbg.finish_non_exhaustive()
+        } else {
+            dbg.finish()
+        }
+    }
+}
+
+/// # Description
+/// A view into a single cache slot, returned by `LruCache::entry(..)`
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    cache: &'a mut LruCache<K, V>,
+    node_ptr: u32,
+}
+
+pub struct VacantEntry<'a, K, V> {
+    cache: &'a mut LruCache<K, V>,
+    key: K,
+}
+
+impl<'a, K, V> Entry<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// # Panics
+    /// when inserting into a cache of size 0
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// # Panics
+    /// when inserting into a cache of size 0
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// # Panics
+    /// when inserting into a cache of size 0
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    fn pair(&self) -> &(K, V) {
+        self.cache.list[self.node_ptr].get_data().expect("val should exist")
+    }
+
+    fn pair_mut(&mut self) -> &mut (K, V) {
+        self.cache.list[self.node_ptr].get_data_mut().expect("val should exist")
+    }
+
+    pub fn key(&self) -> &K {
+        &self.pair().0
+    }
+
+    pub fn get(&self) -> &V {
+        &self.pair().1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.pair_mut().1
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.cache.list[self.node_ptr].get_data_mut().expect("val should exist").1
+    }
+
+    /// replaces the value, returning the old one
+    pub fn insert(&mut self, val: V) -> V {
+        std::mem::replace(self.get_mut(), val)
+    }
+
+    /// takes the entry out of the cache
+    pub fn remove(self) -> V {
+        self.cache.unlink(self.node_ptr).1
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// inserts the value as the MRU entry, evicting LRU pairs when the cache is full.
+    /// Evicted pairs are dropped unless there's an eviction handler
+    /// # Panics
+    /// when the cache has size 0 or the value is heavier than the whole cache, there's nowhere
+    /// to put it. `try_insert(..)` doesn't panic
+    pub fn insert(self, val: V) -> &'a mut V {
+        let zero_size = self.cache.cache_size == 0;
+        match self.try_insert(val) {
+            Ok(val) => val,
+            Err(_) if zero_size => panic!("cannot insert into an LruCache of size 0"),
+            Err(_) => panic!("value is heavier than the whole LruCache"),
+        }
+    }
+
+    /// same as `insert(..)` but a pair that can't be cached is handed back
+    /// # Returns
+    /// `Err` with the key and value when the cache has size 0 or the value is heavier than the
+    /// whole cache. The eviction handler never sees a rejected pair
+    pub fn try_insert(self, val: V) -> Result<&'a mut V, (K, V)> {
+        let weight = self.cache.weigh(&self.key, &val);
+        if self.cache.cache_size == 0 || weight > self.cache.cache_size {
+            return Err((self.key, val));
+        }
+        let node_ptr = self.cache.insert_new(self.key, val, weight);
+        self.cache.evict_overflow();
+        Ok(&mut self.cache.list[node_ptr].get_data_mut().expect("val should exist").1)
+    }
+}
+
+/// # Description
+/// A segmented LRU cache, resistant to scans
+/// # Comments
+/// New keys land in a probation segment and graduate to a protected segment the next time
+/// they're hit. Each segment is an `LruCache` with its own size. When the protected segment
+/// overflows its LRU pair is demoted back to the front of probation, so only pairs that fall
+/// off the end of probation really leave the cache. A scan over lots of cold keys only churns
+/// probation and leaves the protected working set alone
+pub struct SlruCache<K, V> {
+    probation: LruCache<K, V>,
+    protected: LruCache<K, V>,
+    stats: CacheStats,
+}
+
+impl<K, V> SlruCache<K, V>
+where
+    K: Eq + Hash,
+{
+    /// # Description
+    /// Creates an SlruCache holding up to `probation_size + protected_size` entries
+    pub fn new(probation_size: usize, protected_size: usize) -> Self {
+        Self {
+            probation: LruCache::new(probation_size),
+            protected: LruCache::new(protected_size),
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.probation.len() + self.protected.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// # Description
+    /// combined size of both segments
+    pub fn capacity(&self) -> usize {
+        self.probation.capacity() + self.protected.capacity()
+    }
+
+    /// # Description
+    /// fetches value associated with `key`, a hit on probation promotes the pair to protected
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.protected.contains_key(key) {
+            self.stats.record_lookup(true);
+            return self.protected.get(key);
+        }
+
+        let pair = self.probation.remove_entry(key);
+        self.stats.record_lookup(pair.is_some());
+        let (owned_key, val) = pair?;
+        if let Some((demoted_key, demoted_val)) = self.protected.put(owned_key, val) {
+            self.demote(demoted_key, demoted_val);
+        }
+        // a protected segment of size 0 bounces the pair straight back into probation
+        if self.protected.contains_key(key) {
+            self.protected.peek_mut(key)
+        } else {
+            self.probation.peek_mut(key)
+        }
+    }
+
+    /// pushes a pair to the front of probation, whatever falls off the end is a real eviction
+    fn demote(&mut self, key: K, val: V) -> Option<(K, V)> {
+        let evicted = self.probation.put(key, val);
+        if evicted.is_some() {
+            self.stats.evictions += 1;
+        }
+        evicted
+    }
+
+    /// # Description
+    /// fetches value associated with `key` without touching recency or segments
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let val = self.protected.peek(key).or_else(|| self.probation.peek(key));
+        self.stats.record_lookup(val.is_some());
+        val
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.protected.contains_key(key) || self.probation.contains_key(key)
+    }
+
+    /// # Description
+    /// Puts a `key`-`value` pair into the cache. A cached key keeps its segment, new keys start
+    /// out on probation
+    /// # Returns
+    /// the pair evicted off the end of probation, if any
+    pub fn put(&mut self, key: K, val: V) -> Option<(K, V)> {
+        if self.protected.contains_key(&key) {
+            return self.protected.put(key, val);
+        }
+        if !self.probation.contains_key(&key) {
+            self.stats.insertions += 1;
+        }
+        self.demote(key, val)
+    }
+
+    /// # Description
+    /// removes `key` from whichever segment holds it and returns its value
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.protected.remove(key).or_else(|| self.probation.remove(key))
+    }
+
+    /// # Description
+    /// Walks protected MRU to LRU, then probation MRU to LRU. Backwards that's the order pairs
+    /// would be evicted in, ignoring demotions
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &(K, V)> {
+        self.protected.iter().chain(self.probation.iter())
+    }
+
+    /// # Description
+    /// number of `get`/`peek` lookups that found their key
+    pub fn hits(&self) -> u64 {
+        self.stats.hits()
+    }
+
+    /// # Description
+    /// number of `get`/`peek` lookups that came up empty
+    pub fn misses(&self) -> u64 {
+        self.stats.misses()
+    }
+
+    /// # Description
+    /// number of pairs that fell off the end of probation, demotions don't count
+    pub fn evictions(&self) -> u64 {
+        self.stats.evictions
+    }
+
+    /// # Description
+    /// number of new keys inserted, promotions and demotions don't count
+    pub fn insertions(&self) -> u64 {
+        self.stats.insertions
+    }
+
+    /// # Description
+    /// `hits / (hits + misses)`, 0.0 before the first lookup
+    pub fn hit_rate(&self) -> f64 {
+        self.stats.hit_rate()
+    }
+
+    /// # Description
+    /// zeroes every counter, the cached entries are untouched
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+}
+
+/// # Description
+/// A thread-safe LRU cache made of several `LruCache` shards, each behind its own `Mutex`
+/// # Comments
+/// Keys are spread across shards by hash so threads working on different keys rarely wait on
+/// each other. Recency is tracked per shard, so the pair evicted is the LRU one of its shard and
+/// not necessarily of the whole cache
+pub struct ConcurrentLruCache<K, V> {
+    shards: Vec<Mutex<LruCache<K, V>>>,
+    hasher: RandomState,
+}
+
+impl<K, V> ConcurrentLruCache<K, V>
+where
+    K: Eq + Hash,
+{
+    /// # Description
+    /// Creates a cache holding up to `cache_size` entries, with one shard per available core
+    pub fn new(cache_size: usize) -> Self {
+        let shards = thread::available_parallelism().map_or(4, |n| n.get());
+        Self::with_shards(cache_size, shards)
+    }
+
+    /// # Description
+    /// Creates a cache holding up to `cache_size` entries split across `shards` shards
+    /// # Comments
+    /// The shard count is capped at `cache_size` (and raised to 1) so every shard can hold
+    /// at least one entry. The shard sizes always add up to exactly `cache_size`
+    pub fn with_shards(cache_size: usize, shards: usize) -> Self {
+        let shards = shards.min(cache_size).max(1);
+        let shards = (0..shards)
+            .map(|i| {
+                let extra = usize::from(i < cache_size % shards);
+                Mutex::new(LruCache::new(cache_size / shards + extra))
+            })
+            .collect();
+        Self {
+            shards,
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn shard<Q>(&self, key: &Q) -> MutexGuard<'_, LruCache<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.hasher.hash_one(key) as usize % self.shards.len();
+        Self::lock(&self.shards[idx])
+    }
+
+    fn lock(shard: &Mutex<LruCache<K, V>>) -> MutexGuard<'_, LruCache<K, V>> {
+        shard.lock().expect("a thread panicked while holding a shard")
+    }
+
+    /// # Description
+    /// the sum of every shard's stat, shards are locked one at a time so it's only a snapshot
+    fn sum<F: Fn(&LruCache<K, V>) -> u64>(&self, stat: F) -> u64 {
+        self.shards.iter().map(|shard| stat(&Self::lock(shard))).sum()
+    }
+
+    /// # Description
+    /// fetches a copy of the value associated with `key`, its priority gets upgraded
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.shard(key).get(key).cloned()
+    }
+
+    /// # Description
+    /// Puts a `key`-`value` pair into the cache, see `LruCache::put(..)`
+    pub fn put(&self, key: K, val: V) -> Option<(K, V)> {
+        self.shard(&key).put(key, val)
+    }
+
+    /// # Description
+    /// removes `key` from the cache and returns its value
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard(key).remove(key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard(key).contains_key(key)
+    }
+
+    /// # Description
+    /// number of entries across all shards, only a snapshot while other threads are writing
+    pub fn len(&self) -> usize {
+        self.sum(|shard| shard.len() as u64) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// # Description
+    /// the combined size of every shard
+    pub fn capacity(&self) -> usize {
+        self.sum(|shard| shard.capacity() as u64) as usize
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.sum(LruCache::hits)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.sum(LruCache::misses)
+    }
+
+    pub fn evictions(&self) -> u64 {
+        self.sum(LruCache::evictions)
+    }
+
+    pub fn insertions(&self) -> u64 {
+        self.sum(LruCache::insertions)
+    }
+
+    /// # Description
+    /// `hits / (hits + misses)` over every shard, 0.0 before the first lookup
+    pub fn hit_rate(&self) -> f64 {
+        hit_rate(self.hits(), self.misses())
+    }
+
+    /// # Description
+    /// zeroes every counter of every shard
+    pub fn reset_stats(&self) {
+        self.shards.iter().for_each(|shard| Self::lock(shard).reset_stats());
+    }
+}
+
+/// # Description
+/// `serde` support, enabled with the `serde` feature
+/// # Comments
+/// A cache is written as its `cache_size` plus its pairs from LRU to MRU, node pointers and the
+/// hashtable never hit the wire. Deserializing re-puts the pairs in that order so the recency
+/// ranking comes back the same. Stats, TTLs, the weigher and the eviction handler aren't saved,
+/// the cache comes back as a plain one
+#[cfg(feature = "serde")]
+mod lru_serde {
+    use super::*;
+    use serde::{
+        de::{self, MapAccess, SeqAccess, Visitor},
+        ser::{SerializeSeq, SerializeStruct},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{fmt, marker::PhantomData};
+
+    const FIELDS: &[&str] = &["cache_size", "entries"];
+
+    enum Field {
+        CacheSize,
+        Entries,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct FieldVisitor;
+
+            impl<'de> Visitor<'de> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "`cache_size` or `entries`")
+                }
+
+                fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+                    match value {
+                        "cache_size" => Ok(Field::CacheSize),
+                        "entries" => Ok(Field::Entries),
+                        _ => Err(E::unknown_field(value, FIELDS)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct Entries<'a, K, V>(&'a LruCache<K, V>);
+
+    impl<'a, K, V> Serialize for Entries<'a, K, V>
+    where
+        K: Serialize + Eq + Hash,
+        V: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+            for pair in self.0.iter_lru_first() {
+                seq.serialize_element(pair)?;
+            }
+            seq.end()
+        }
+    }
+
+    impl<K, V> Serialize for LruCache<K, V>
+    where
+        K: Serialize + Eq + Hash,
+        V: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("LruCache", 2)?;
+            state.serialize_field("cache_size", &self.cache_size)?;
+            state.serialize_field("entries", &Entries(self))?;
+            state.end()
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for LruCache<K, V>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_struct("LruCache", FIELDS, CacheVisitor(PhantomData))
+        }
+    }
+
+    struct CacheVisitor<K, V>(PhantomData<(K, V)>);
+
+    impl<K, V> CacheVisitor<K, V>
+    where
+        K: Eq + Hash,
+    {
+        fn build(cache_size: usize, entries: Vec<(K, V)>) -> LruCache<K, V> {
+            let mut cache = LruCache::new(cache_size);
+            for (key, val) in entries {
+                cache.put(key, val);
+            }
+            cache.reset_stats();
+            cache
+        }
+    }
+
+    impl<'de, K, V> Visitor<'de> for CacheVisitor<K, V>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+    {
+        type Value = LruCache<K, V>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "an lru cache with `cache_size` and `entries` fields")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let cache_size = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let entries = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            Ok(Self::build(cache_size, entries))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut cache_size = None;
+            let mut entries = None;
+            while let Some(field) = map.next_key()? {
+                match field {
+                    Field::CacheSize => cache_size = Some(map.next_value()?),
+                    Field::Entries => entries = Some(map.next_value()?),
+                }
+            }
+            let cache_size = cache_size.ok_or_else(|| de::Error::missing_field("cache_size"))?;
+            let entries = entries.ok_or_else(|| de::Error::missing_field("entries"))?;
+            Ok(Self::build(cache_size, entries))
+        }
+    }
+}
+
+#[test]
+fn simple_test(){
+    let to_vec = |c:&LruCache<String,i32>| -> Vec<_>{
+        c.keys().cloned().collect()
+    };
+
+    let tostr = |a|{
+        String::from(a)
+    };
+
+    let mut lru = LruCache::<String, i32>::new(4);
+
+    lru.put(String::from("a"), 1);
+    assert_eq!( to_vec(&lru) , [tostr("a")] );
+
+    lru.put(String::from("b"), 1);
+    assert_eq!( to_vec(&lru) , [tostr("b"),tostr("a")] );
+
+    lru.put(String::from("c"), 1);
+    assert_eq!( to_vec(&lru) , [tostr("c"),tostr("b"),tostr("a")] );
+
+    lru.put(String::from("d"), 1);
+    assert_eq!( to_vec(&lru) , [tostr("d"),tostr("c"),tostr("b"),tostr("a")] );
+
+    lru.put(String::from("e"), 1);
+    assert_eq!( to_vec(&lru) , [tostr("e"),tostr("d"),tostr("c"),tostr("b")]);
+
+    lru.get(&String::from("b"));
+    assert_eq!( to_vec(&lru) , [tostr("b"),tostr("e"),tostr("d"),tostr("c")]);
 
     lru.get(&String::from("c"));
     assert_eq!( to_vec(&lru) , [tostr("c"),tostr("b"),tostr("e"),tostr("d")]);
-}
\ No newline at end of file
+}
+
+#[test]
+fn put_same_key_while_warming_up_test() {
+    let mut lru = LruCache::<&str, i32>::new(4);
+    for k in 0..10 {
+        lru.put("a", k);
+    }
+    assert_eq!(lru.iter().collect::<Vec<_>>(), [&("a", 9)]);
+    assert_eq!(lru.list.len(), 1);
+    assert_eq!(lru.key_table.len, 1);
+
+    lru.put("b", 1);
+    lru.put("a", 10);
+    lru.put("c", 1);
+    assert_eq!(lru.iter().collect::<Vec<_>>(), [&("c", 1), &("a", 10), &("b", 1)]);
+
+    // filling up and evicting keeps the table in sync with the list
+    lru.put("d", 1);
+    lru.put("e", 1);
+    lru.put("f", 1);
+    assert_eq!(lru.iter().map(|(k, _)| *k).collect::<Vec<_>>(), ["f", "e", "d", "c"]);
+    assert_eq!(lru.key_table.len, 4);
+    for (ptr, (key, _)) in lru.list.iter_ptr() {
+        assert_eq!(lru.find(key), Some(ptr));
+    }
+}
+
+#[test]
+fn put_returns_evicted_test() {
+    let mut lru = LruCache::<String, i32>::new(3);
+    assert_eq!(lru.put("a".to_string(), 1), None);
+    assert_eq!(lru.put("b".to_string(), 2), None);
+    assert_eq!(lru.put("c".to_string(), 3), None);
+    // updating an existing key never evicts
+    assert_eq!(lru.put("a".to_string(), 10), None);
+    lru.get(&"b".to_string());
+
+    for (k, key) in ["d", "e", "f", "g"].iter().enumerate() {
+        let lru_entry = lru.iter().last().cloned();
+        assert_eq!(lru.put(key.to_string(), k as i32), lru_entry);
+    }
+    assert_eq!(lru.put("e".to_string(), 0), None);
+}
+
+#[test]
+fn peek_test() {
+    let mut lru = LruCache::<&str, i32>::new(3);
+    lru.put("a", 1);
+    lru.put("b", 2);
+    lru.put("c", 3);
+    assert_eq!(lru.peek(&"z"), None);
+
+    // "a" is the lru entry, peeking it doesn't save it from eviction
+    assert_eq!(lru.peek(&"a"), Some(&1));
+    *lru.peek_mut(&"a").unwrap() += 10;
+    assert_eq!(lru.peek(&"a"), Some(&11));
+    assert_eq!(lru.iter().map(|(k, _)| *k).collect::<Vec<_>>(), ["c", "b", "a"]);
+
+    assert_eq!(lru.put("d", 4), Some(("a", 11)));
+    assert_eq!(lru.peek(&"b"), Some(&2));
+    assert_eq!(lru.put("e", 5), Some(("b", 2)));
+    assert_eq!(lru.peek(&"a"), None);
+}
+
+#[test]
+fn contains_key_test() {
+    let mut lru = LruCache::<String, i32>::new(2);
+    assert!(!lru.contains_key(&"a".to_string()));
+    lru.put("a".to_string(), 1);
+    lru.put("b".to_string(), 2);
+    assert!(lru.contains_key(&"a".to_string()));
+
+    // checking didn't promote "a" so it's still the one evicted
+    lru.put("c".to_string(), 3);
+    assert!(!lru.contains_key(&"a".to_string()));
+    assert!(lru.contains_key(&"b".to_string()));
+    assert!(lru.contains_key(&"c".to_string()));
+}
+
+#[test]
+fn remove_test() {
+    let mut lru = LruCache::<String, i32>::new(3);
+    lru.put("a".to_string(), 1);
+    lru.put("b".to_string(), 2);
+    lru.put("c".to_string(), 3);
+
+    assert_eq!(lru.remove(&"z".to_string()), None);
+    assert_eq!(lru.list.len(), 3);
+
+    assert_eq!(lru.remove(&"b".to_string()), Some(2));
+    assert_eq!(lru.remove(&"b".to_string()), None);
+    assert_eq!(lru.list.len(), 2);
+    assert_eq!(lru.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(), ["c", "a"]);
+
+    // there's room again, so putting "b" back is a fresh insert that evicts nothing
+    assert_eq!(lru.put("b".to_string(), 20), None);
+    assert_eq!(lru.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(), ["b", "c", "a"]);
+    assert_eq!(lru.list.pool_len(), 0);
+    assert_eq!(lru.put("d".to_string(), 4), Some(("a".to_string(), 1)));
+}
+
+#[test]
+fn pop_lru_test() {
+    let mut lru = LruCache::<String, i32>::new(3);
+    assert_eq!(lru.pop_lru(), None);
+
+    lru.put("a".to_string(), 1);
+    lru.put("b".to_string(), 2);
+    lru.put("c".to_string(), 3);
+    lru.get(&"a".to_string());
+    assert_eq!(lru.pop_lru(), Some(("b".to_string(), 2)));
+    assert!(!lru.contains_key(&"b".to_string()));
+    assert_eq!(lru.pop_lru(), Some(("c".to_string(), 3)));
+
+    // pop/put cycles reuse the freed nodes
+    for k in 0..100 {
+        lru.put(k.to_string(), k);
+        lru.put((k + 1000).to_string(), k);
+        lru.pop_lru();
+        lru.pop_lru();
+    }
+    assert_eq!(lru.list.len() + lru.list.pool_len(), 3);
+    assert_eq!(lru.key_table.len, lru.list.len());
+
+    assert!(lru.pop_lru().is_some());
+    assert_eq!(lru.pop_lru(), None);
+    assert_eq!(lru.key_table.len, 0);
+}
+
+#[test]
+fn pop_mru_test() {
+    let keys = |c: &LruCache<&'static str, i32>| c.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    let mut lru = LruCache::<&str, i32>::new(4);
+    assert_eq!(lru.pop_mru(), None);
+
+    lru.put("a", 1);
+    lru.put("b", 2);
+    lru.put("c", 3);
+    lru.get(&"a");
+    assert_eq!(keys(&lru), ["a", "c", "b"]);
+
+    // undoing a speculative insert leaves the rest in the same order
+    lru.put("x", 0);
+    assert_eq!(lru.pop_mru(), Some(("x", 0)));
+    assert_eq!(keys(&lru), ["a", "c", "b"]);
+    assert!(!lru.contains_key(&"x"));
+
+    lru.get(&"b");
+    assert_eq!(lru.pop_mru(), Some(("b", 2)));
+    assert_eq!(keys(&lru), ["a", "c"]);
+    lru.put("d", 4);
+    lru.put("e", 5);
+    lru.put("f", 6);
+    assert_eq!(keys(&lru), ["f", "e", "d", "a"]);
+    assert_eq!(lru.key_table.len, 4);
+}
+
+#[test]
+fn len_capacity_test() {
+    let mut lru = LruCache::<&str, i32>::new(3);
+    assert_eq!((lru.len(), lru.is_empty(), lru.capacity()), (0, true, 3));
+
+    for k in 0..5 {
+        lru.put("a", k);
+    }
+    assert_eq!(lru.len(), 1);
+    assert!(!lru.is_empty());
+
+    lru.put("b", 0);
+    lru.put("c", 0);
+    lru.put("d", 0);
+    assert_eq!(lru.len(), 3);
+    lru.remove(&"d");
+    lru.pop_lru();
+    assert_eq!(lru.len(), 1);
+    lru.pop_mru();
+    assert_eq!((lru.len(), lru.is_empty(), lru.capacity()), (0, true, 3));
+}
+
+#[test]
+fn clear_test() {
+    let mut lru = LruCache::<String, i32>::new(8);
+    for k in 0..8 {
+        lru.put(k.to_string(), k);
+    }
+    lru.clear();
+    assert_eq!(lru.len(), 0);
+    assert_eq!(lru.iter().count(), 0);
+    assert_eq!(lru.get(&"3".to_string()), None);
+    assert!(!lru.contains_key(&"0".to_string()));
+    assert_eq!(lru.list.pool_len(), 8);
+
+    // refilling reuses the nodes
+    for k in 0..8 {
+        assert_eq!(lru.put(k.to_string(), -k), None);
+    }
+    assert_eq!(lru.list.pool_len(), 0);
+    assert_eq!(lru.get(&"3".to_string()), Some(&mut -3));
+
+    lru.clear_shrink();
+    assert!(lru.is_empty());
+    assert_eq!(lru.list.pool_len(), 0);
+    assert!(lru.key_table.slots.is_empty());
+    lru.put("a".to_string(), 1);
+    assert_eq!(lru.peek(&"a".to_string()), Some(&1));
+}
+
+#[test]
+fn resize_test() {
+    let keys = |c: &LruCache<i32, i32>| c.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    let mut lru = LruCache::<i32, i32>::new(5);
+    for k in 0..5 {
+        lru.put(k, k * 10);
+    }
+    lru.get(&1);
+    lru.get(&0);
+    assert_eq!(keys(&lru), [0, 1, 4, 3, 2]);
+
+    // shrinking evicts the coldest entries, coldest first
+    assert_eq!(lru.resize(2), [(2, 20), (3, 30), (4, 40)]);
+    assert_eq!(keys(&lru), [0, 1]);
+    assert_eq!(lru.capacity(), 2);
+    assert_eq!(lru.put(7, 70), Some((1, 10)));
+
+    // growing only raises the limit
+    assert_eq!(lru.resize(4), []);
+    assert_eq!(lru.put(8, 80), None);
+    assert_eq!(lru.put(9, 90), None);
+    assert_eq!(keys(&lru), [9, 8, 7, 0]);
+    assert_eq!(lru.put(10, 100), Some((0, 0)));
+
+    // a zero sized cache holds nothing
+    assert_eq!(lru.resize(0).len(), 4);
+    assert!(lru.is_empty());
+    assert_eq!(lru.put(1, 1), Some((1, 1)));
+    assert!(lru.is_empty());
+    assert_eq!(lru.resize(1), []);
+    assert_eq!(lru.put(1, 1), None);
+    assert_eq!(keys(&lru), [1]);
+}
+
+#[test]
+fn entry_test() {
+    let keys = |c: &LruCache<&'static str, i32>| c.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    let mut lru = LruCache::<&str, i32>::new(3);
+
+    *lru.entry("a").or_insert(0) += 1;
+    *lru.entry("a").or_insert(0) += 1;
+    lru.entry("b").or_insert_with(|| 10);
+    *lru.entry("c").or_default() += 5;
+    assert_eq!(lru.iter().cloned().collect::<Vec<_>>(), [("c", 5), ("b", 10), ("a", 2)]);
+    assert_eq!(lru.len(), 3);
+
+    // touching an occupied entry promotes it
+    lru.entry("a").and_modify(|v| *v *= 100).or_insert(0);
+    assert_eq!(keys(&lru), ["a", "c", "b"]);
+    assert_eq!(lru.peek(&"a"), Some(&200));
+
+    // vacant inserts respect the capacity
+    lru.entry("d").and_modify(|v| *v = -1).or_insert(4);
+    assert_eq!(keys(&lru), ["d", "a", "c"]);
+    assert!(!lru.contains_key(&"b"));
+    assert_eq!(lru.key_table.len, 3);
+
+    match lru.entry("c") {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), &"c");
+            assert_eq!(entry.insert(50), 5);
+            assert_eq!(entry.get(), &50);
+            assert_eq!(entry.remove(), 50);
+        }
+        Entry::Vacant(_) => panic!("c should be cached"),
+    }
+    assert_eq!(keys(&lru), ["d", "a"]);
+    match lru.entry("z") {
+        Entry::Vacant(entry) => assert_eq!(entry.into_key(), "z"),
+        Entry::Occupied(_) => panic!("z shouldn't be cached"),
+    }
+    assert_eq!(lru.entry("q").key(), &"q");
+    assert_eq!(keys(&lru), ["d", "a"]);
+}
+
+#[test]
+fn entry_try_insert_test() {
+    fn vacant<'a, K, V>(entry: Entry<'a, K, V>) -> VacantEntry<'a, K, V> {
+        match entry {
+            Entry::Vacant(entry) => entry,
+            Entry::Occupied(_) => panic!("key should be vacant"),
+        }
+    }
+
+    // nowhere to put anything
+    let mut lru = LruCache::<&str, i32>::new(0);
+    assert_eq!(vacant(lru.entry("a")).try_insert(1), Err(("a", 1)));
+    assert!(lru.is_empty());
+
+    let mut lru = LruCache::with_weigher(4, |_: &&str, v: &i32| *v as usize);
+    assert_eq!(vacant(lru.entry("a")).try_insert(3), Ok(&mut 3));
+    assert_eq!(vacant(lru.entry("b")).try_insert(5), Err(("b", 5)));
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), ["a"]);
+
+    // fits once the LRU pair is evicted
+    *vacant(lru.entry("c")).try_insert(2).unwrap() += 1;
+    assert_eq!(lru.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(), [("c", 3)]);
+    assert_eq!(lru.current_weight(), 2);
+}
+
+#[test]
+fn iter_mut_test() {
+    let mut lru = LruCache::<&str, f32>::new(3);
+    lru.put("a", 1.0);
+    lru.put("b", 2.0);
+    lru.put("c", 4.0);
+    lru.get(&"a");
+
+    // decay every counter
+    for (_, v) in lru.iter_mut() {
+        *v /= 2.0;
+    }
+    let seen = lru.iter_mut().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+    assert_eq!(seen, [("a", 0.5), ("c", 2.0), ("b", 1.0)]);
+    assert_eq!(lru.iter().cloned().collect::<Vec<_>>(), seen);
+    assert_eq!(lru.put("d", 0.0), Some(("b", 1.0)));
+}
+
+#[test]
+fn keys_values_test() {
+    let mut lru = LruCache::<String, i32>::new(3);
+    assert_eq!(lru.keys().len(), 0);
+    for k in 0..4 {
+        lru.put(k.to_string(), k);
+    }
+    lru.get(&"2".to_string());
+
+    assert_eq!(lru.keys().cloned().collect::<Vec<_>>(), ["2", "3", "1"]);
+    assert_eq!(lru.values().copied().collect::<Vec<_>>(), [2, 3, 1]);
+    assert_eq!(lru.keys().len(), lru.len());
+    assert_eq!(lru.values().rev().copied().collect::<Vec<_>>(), [1, 3, 2]);
+
+    lru.values_mut().for_each(|v| *v *= 10);
+    assert_eq!(lru.values_mut().len(), 3);
+    assert_eq!(lru.values().copied().collect::<Vec<_>>(), [20, 30, 10]);
+    assert_eq!(lru.keys().next().map(|k| k.as_str()), Some("2"));
+}
+
+#[test]
+fn iter_lru_first_test() {
+    let mut lru = LruCache::<char, u32>::new(4);
+    assert_eq!(lru.iter_lru_first().next(), None);
+
+    // scripted accesses, recency ranking ends up d (hottest), a, e, c
+    for (k, key) in "abcd".chars().enumerate() {
+        lru.put(key, k as u32);
+    }
+    lru.get(&'a');
+    lru.get(&'c');
+    lru.put('e', 4);
+    lru.get(&'a');
+    lru.peek(&'c');
+    lru.put('d', 30);
+
+    let cold_first = lru.iter_lru_first().map(|(k, _)| *k).collect::<String>();
+    assert_eq!(cold_first, "cead");
+    assert_eq!(lru.iter().rev().map(|(k, _)| *k).collect::<String>(), cold_first);
+    assert_eq!(lru.keys().collect::<String>(), "daec");
+    assert_eq!(lru.iter_lru_first().next(), Some(&('c', 2)));
+    assert_eq!(lru.iter_mut().next_back().map(|(k, _)| *k), Some('c'));
+
+    // warming a fresh cache in eviction order keeps the same ranking
+    let mut warm = LruCache::<char, u32>::new(4);
+    for &(k, v) in lru.iter_lru_first() {
+        warm.put(k, v);
+    }
+    assert_eq!(warm.iter().collect::<Vec<_>>(), lru.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn stats_test() {
+    let mut lru = LruCache::<i32, i32>::new(2);
+    assert_eq!(lru.hit_rate(), 0.0);
+
+    lru.put(1, 10);
+    lru.put(2, 20);
+    lru.put(1, 11); // update, not an insertion
+    assert_eq!(lru.get(&1), Some(&mut 11)); // hit
+    assert_eq!(lru.get(&3), None); // miss
+    assert_eq!(lru.peek(&2), Some(&20)); // hit
+    lru.put(3, 30); // evicts 2
+    assert_eq!(lru.peek(&2), None); // miss
+    *lru.entry(4).or_insert(40) += 1; // miss, evicts 1
+    *lru.entry(4).or_insert(0) += 1; // hit
+    assert!(!lru.contains_key(&1)); // not a lookup
+
+    assert_eq!(lru.hits(), 3);
+    assert_eq!(lru.misses(), 3);
+    assert_eq!(lru.insertions(), 4);
+    assert_eq!(lru.evictions(), 2);
+    assert_eq!(lru.hit_rate(), 0.5);
+
+    assert_eq!(lru.resize(1).len(), 1);
+    assert_eq!(lru.evictions(), 3);
+    lru.pop_lru();
+    assert_eq!(lru.evictions(), 3);
+
+    lru.reset_stats();
+    assert_eq!((lru.hits(), lru.misses(), lru.insertions(), lru.evictions()), (0, 0, 0, 0));
+    assert_eq!(lru.hit_rate(), 0.0);
+}
+
+#[test]
+fn eviction_handler_test() {
+    use std::sync::{Arc, Mutex};
+
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&evicted);
+    let mut lru = LruCache::with_eviction_handler(2, move |k, v| sink.lock().unwrap().push((k, v)));
+
+    assert_eq!(lru.put(1, 10), None);
+    assert_eq!(lru.put(2, 20), None);
+    assert_eq!(lru.put(3, 30), None);
+    assert_eq!(*evicted.lock().unwrap(), [(1, 10)]);
+
+    lru.entry(4).or_insert(40);
+    assert_eq!(*evicted.lock().unwrap(), [(1, 10), (2, 20)]);
+
+    // explicit removals don't go through the handler
+    assert_eq!(lru.remove(&3), Some(30));
+    assert_eq!(lru.pop_lru(), Some((4, 40)));
+    assert_eq!(evicted.lock().unwrap().len(), 2);
+
+    lru.put(5, 50);
+    lru.put(6, 60);
+    assert!(lru.resize(0).is_empty());
+    assert_eq!(lru.put(7, 70), None);
+    assert_eq!(*evicted.lock().unwrap(), [(1, 10), (2, 20), (5, 50), (6, 60), (7, 70)]);
+    assert!(lru.is_empty());
+    assert_eq!(lru.evictions(), 4);
+}
+
+#[test]
+fn ttl_test() {
+    use std::sync::{Arc, Mutex};
+
+    let start = Instant::now();
+    let now = Arc::new(Mutex::new(start));
+    let clock = Arc::clone(&now);
+    let advance = |secs| *now.lock().unwrap() += Duration::from_secs(secs);
+    let mut lru = LruCache::<i32, i32>::new(4).with_clock(move || *clock.lock().unwrap());
+
+    lru.put_with_ttl(1, 10, Duration::from_secs(5));
+    lru.put_with_ttl(2, 20, Duration::from_secs(10));
+    lru.put(3, 30);
+    advance(4);
+    assert_eq!(lru.get(&1), Some(&mut 10));
+
+    // recency doesn't extend a TTL
+    advance(1);
+    assert!(!lru.contains_key(&1));
+    assert_eq!(lru.peek(&1), None);
+    assert_eq!(lru.len(), 3);
+    assert_eq!(lru.get(&1), None);
+    assert_eq!(lru.len(), 2);
+    assert_eq!(lru.misses(), 2);
+
+    // a plain put on an expiring key drops the TTL
+    lru.put_with_ttl(4, 40, Duration::from_secs(1));
+    lru.put(2, 21);
+    advance(100);
+    assert_eq!(lru.get(&2), Some(&mut 21));
+    assert_eq!(lru.get(&3), Some(&mut 30));
+    assert_eq!(lru.entry(4).or_insert(41), &mut 41);
+
+    lru.put_with_ttl(5, 50, Duration::from_secs(1));
+    lru.put_with_ttl(6, 60, Duration::from_secs(3));
+    assert_eq!(lru.len(), 4);
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), [6, 5, 4, 3]);
+    assert_eq!(lru.purge_expired(start + Duration::from_secs(107)), 1);
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), [6, 4, 3]);
+    assert_eq!(lru.purge_expired(start + Duration::from_secs(200)), 1);
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), [4, 3]);
+
+    // pool nodes get reused, the new entry mustn't inherit the old deadline
+    lru.put(7, 70);
+    assert_eq!(lru.peek(&7), Some(&70));
+    assert_eq!(lru.key_table.len, lru.len());
+
+    // a TTL past what `Instant` can hold is no deadline at all
+    lru.put_with_ttl(8, 80, Duration::MAX);
+    advance(1_000_000);
+    assert_eq!(lru.get(&8), Some(&mut 80));
+}
+
+#[test]
+fn weigher_test() {
+    let mut lru = LruCache::with_weigher(10, |_: &i32, v: &Vec<u8>| v.len());
+    assert_eq!(lru.capacity(), 10);
+    lru.put(1, vec![0; 3]);
+    lru.put(2, vec![0; 3]);
+    lru.put(3, vec![0; 3]);
+    assert_eq!(lru.current_weight(), 9);
+
+    // one huge value pushes everything else out, coldest comes back
+    assert_eq!(lru.put(4, vec![0; 10]), Some((1, vec![0; 3])));
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), [4]);
+    assert_eq!(lru.current_weight(), 10);
+    assert_eq!(lru.evictions(), 3);
+
+    // too heavy to ever fit, the stale value goes too
+    assert_eq!(lru.put(4, vec![0; 11]), Some((4, vec![0; 11])));
+    assert!(lru.is_empty());
+    assert_eq!(lru.current_weight(), 0);
+
+    // growing a value re-balances from the cold end
+    lru.put(1, vec![0; 2]);
+    lru.put(2, vec![0; 2]);
+    lru.put(3, vec![0; 2]);
+    lru.put(4, vec![0; 2]);
+    assert_eq!(lru.put(2, vec![0; 5]), Some((1, vec![0; 2])));
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), [2, 4, 3]);
+    assert_eq!(lru.current_weight(), 9);
+    assert_eq!(lru.put(2, vec![0; 1]), None);
+    assert_eq!(lru.current_weight(), 5);
+
+    assert_eq!(lru.remove(&4), Some(vec![0; 2]));
+    assert_eq!(lru.current_weight(), 3);
+    assert_eq!(lru.resize(2), [(3, vec![0; 2])]);
+    assert_eq!(lru.current_weight(), 1);
+    lru.entry(5).or_insert(vec![0; 2]);
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), [5]);
+    assert_eq!(lru.current_weight(), 2);
+
+    // with a handler every pair evicted by one put reaches it, coldest first
+    use std::sync::{Arc, Mutex};
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&evicted);
+    lru.on_evict = Some(Box::new(move |k, v: Vec<u8>| sink.lock().unwrap().push((k, v.len()))));
+    assert!(lru.resize(10).is_empty());
+    let before = lru.evictions();
+    lru.put(6, vec![0; 3]);
+    lru.put(7, vec![0; 3]);
+    assert_eq!(lru.put(8, vec![0; 9]), None);
+    assert_eq!(*evicted.lock().unwrap(), [(5, 2), (6, 3), (7, 3)]);
+    assert_eq!(lru.current_weight(), 9);
+    assert_eq!(lru.evictions(), before + 3);
+}
+
+#[test]
+fn slru_scan_test() {
+    let mut slru = SlruCache::<i32, i32>::new(4, 4);
+    let mut lru = LruCache::<i32, i32>::new(8);
+    for hot in 0..4 {
+        slru.put(hot, hot);
+        lru.put(hot, hot);
+    }
+    for hot in 0..4 {
+        assert_eq!(slru.get(&hot), Some(&mut hot.clone()));
+        lru.get(&hot);
+    }
+
+    // one pass over a pile of cold keys
+    for cold in 100..200 {
+        assert_eq!(slru.get(&cold), None);
+        slru.put(cold, cold);
+        lru.put(cold, cold);
+    }
+    assert!((0..4).all(|hot| slru.contains_key(&hot)));
+    assert!((0..4).all(|hot| !lru.contains_key(&hot)));
+    assert_eq!(slru.len(), 8);
+    assert_eq!(
+        slru.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        [3, 2, 1, 0, 199, 198, 197, 196]
+    );
+
+    assert_eq!(slru.hits(), 4);
+    assert_eq!(slru.misses(), 100);
+    assert_eq!(slru.insertions(), 104);
+    assert_eq!(slru.evictions(), 96);
+}
+
+#[test]
+fn slru_demote_test() {
+    let mut slru = SlruCache::<i32, i32>::new(2, 1);
+    slru.put(1, 10);
+    slru.put(2, 20);
+    slru.get(&1);
+    assert_eq!(slru.iter().map(|(k, _)| *k).collect::<Vec<_>>(), [1, 2]);
+
+    // promoting 2 overflows protected, 1 goes back to the front of probation
+    assert_eq!(slru.get(&2), Some(&mut 20));
+    slru.put(3, 30);
+    assert_eq!(slru.iter().map(|(k, _)| *k).collect::<Vec<_>>(), [2, 3, 1]);
+
+    // updates keep their segment
+    assert_eq!(slru.put(2, 21), None);
+    assert_eq!(slru.put(4, 40), Some((1, 10)));
+    assert_eq!(slru.iter().map(|(k, _)| *k).collect::<Vec<_>>(), [2, 4, 3]);
+    assert_eq!(slru.peek(&2), Some(&21));
+    assert_eq!(slru.remove(&3), Some(30));
+    assert_eq!(slru.len(), 2);
+
+    // nothing can be protected, promotions bounce back into probation
+    let mut slru = SlruCache::<i32, i32>::new(2, 0);
+    slru.put(1, 10);
+    assert_eq!(slru.get(&1), Some(&mut 10));
+    assert_eq!(slru.len(), 1);
+}
+
+#[test]
+fn concurrent_lru_test() {
+    // the shards only need `LruCache: Send`, the `Mutex` makes them shareable
+    fn assert_send<S: Send>() {}
+    fn assert_send_sync<S: Send + Sync>() {}
+    assert_send::<LruCache<String, String>>();
+    assert_send_sync::<ConcurrentLruCache<String, String>>();
+
+    let cache = ConcurrentLruCache::<u32, u32>::with_shards(64, 8);
+    assert_eq!((cache.capacity(), cache.shard_count()), (64, 8));
+    assert_eq!(ConcurrentLruCache::<u32, u32>::with_shards(3, 8).shard_count(), 3);
+    assert_eq!(ConcurrentLruCache::<u32, u32>::with_shards(0, 8).shard_count(), 1);
+    assert_eq!(ConcurrentLruCache::<u32, u32>::with_shards(10, 4).capacity(), 10);
+
+    thread::scope(|scope| {
+        for t in 0..8u32 {
+            let cache = &cache;
+            scope.spawn(move || {
+                for i in 0..1000 {
+                    let key = (t * 37 + i) % 200;
+                    if let Some(val) = cache.get(&key) {
+                        assert_eq!(val, key * 2);
+                    } else {
+                        cache.put(key, key * 2);
+                    }
+                    if i % 100 == 0 {
+                        cache.remove(&key);
+                    }
+                    assert!(cache.len() <= 64);
+                }
+            });
+        }
+    });
+
+    assert!(cache.len() <= 64);
+    assert_eq!(cache.hits() + cache.misses(), 8000);
+    cache.reset_stats();
+    assert_eq!((cache.hits(), cache.misses()), (0, 0));
+
+    cache.put(1000, 1);
+    assert_eq!(cache.get(&1000), Some(1));
+    assert_eq!(cache.remove(&1000), Some(1));
+    assert!(!cache.contains_key(&1000));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn lru_serde_test() {
+    use serde::{Deserialize, Deserializer};
+    use serde_test::{assert_de_tokens, assert_ser_tokens, Token};
+
+    // LruCache has no PartialEq, compare what iter() sees instead
+    #[derive(Debug, PartialEq)]
+    struct Snapshot(usize, Vec<(i32, char)>);
+
+    impl<'de> Deserialize<'de> for Snapshot {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let cache = LruCache::<i32, char>::deserialize(deserializer)?;
+            Ok(Snapshot(cache.capacity(), cache.iter().copied().collect()))
+        }
+    }
+
+    let mut lru = LruCache::new(3);
+    lru.put(1, 'a');
+    lru.put(2, 'b');
+    lru.put(3, 'c');
+    lru.get(&1);
+
+    let tokens = [
+        Token::Struct {
+            name: "LruCache",
+            len: 2,
+        },
+        Token::Str("cache_size"),
+        Token::U64(3),
+        Token::Str("entries"),
+        Token::Seq { len: Some(3) },
+        Token::Tuple { len: 2 },
+        Token::I32(2),
+        Token::Char('b'),
+        Token::TupleEnd,
+        Token::Tuple { len: 2 },
+        Token::I32(3),
+        Token::Char('c'),
+        Token::TupleEnd,
+        Token::Tuple { len: 2 },
+        Token::I32(1),
+        Token::Char('a'),
+        Token::TupleEnd,
+        Token::SeqEnd,
+        Token::StructEnd,
+    ];
+    assert_ser_tokens(&lru, &tokens);
+    assert_de_tokens(&Snapshot(3, lru.iter().copied().collect()), &tokens);
+    assert_eq!(lru.iter().map(|(k, _)| *k).collect::<Vec<_>>(), [1, 3, 2]);
+}
+
+#[test]
+fn borrowed_key_test() {
+    let mut lru = LruCache::<String, i32>::new(2);
+    lru.put(String::from("a"), 1);
+    lru.put(String::from("b"), 2);
+    assert_eq!(lru.get("a"), Some(&mut 1));
+    assert_eq!(lru.peek("b"), Some(&2));
+    assert_eq!(lru.peek_mut("c"), None);
+    assert!(lru.contains_key("a"));
+    assert_eq!(lru.remove("b"), Some(2));
+    assert_eq!(lru.remove("b"), None);
+    assert_eq!(lru.len(), 1);
+
+    let mut slru = SlruCache::<String, i32>::new(1, 1);
+    slru.put(String::from("a"), 1);
+    assert_eq!(slru.get("a"), Some(&mut 1));
+    assert_eq!(slru.peek("a"), Some(&1));
+    assert_eq!(slru.remove("a"), Some(1));
+    assert!(!slru.contains_key("a"));
+
+    let concurrent = ConcurrentLruCache::<String, i32>::with_shards(4, 2);
+    concurrent.put(String::from("a"), 1);
+    assert_eq!(concurrent.get("a"), Some(1));
+    assert!(concurrent.contains_key("a"));
+    assert_eq!(concurrent.remove("a"), Some(1));
+}
+
+#[test]
+fn retain_test() {
+    let mut lru = LruCache::<i32, i32>::with_weigher(100, |_, v| *v as usize);
+    for k in 0..10 {
+        lru.put(k, k);
+    }
+    lru.get(&4);
+
+    let removed = lru.retain(|k, v| {
+        *v *= 2;
+        k % 2 == 0
+    });
+    assert_eq!(removed, 5);
+    assert_eq!(lru.iter().copied().collect::<Vec<_>>(), [(4, 8), (8, 16), (6, 12), (2, 4), (0, 0)]);
+    // weights come from the values as they were put
+    assert_eq!(lru.current_weight(), 4 + 8 + 6 + 2);
+
+    // the hashtable and the list still agree
+    assert_eq!(lru.list.validate(), Ok(()));
+    assert_eq!(lru.key_table.len, lru.len());
+    for (node_ptr, (key, _)) in lru.list.iter_ptr() {
+        assert_eq!(lru.find(key), Some(node_ptr));
+    }
+
+    assert_eq!(lru.retain(|_, _| true), 0);
+    assert_eq!(lru.retain(|k, _| *k == 0), 4);
+    assert_eq!(lru.retain(|_, _| false), 1);
+    assert!(lru.is_empty() && lru.key_table.len == 0);
+    lru.put(1, 1);
+    assert_eq!(lru.peek(&1), Some(&1));
+}
+
+#[test]
+fn extend_from_iter_test() {
+    let cap = 4;
+    let pairs = (0..2 * cap as i32).map(|k| (k, k * 10));
+    let lru = LruCache::from_iter_with_capacity(cap, pairs);
+    assert_eq!(lru.len(), cap);
+    assert_eq!(lru.iter().copied().collect::<Vec<_>>(), [(7, 70), (6, 60), (5, 50), (4, 40)]);
+
+    let mut lru: LruCache<i32, i32> = (0..3).map(|k| (k, k)).collect();
+    assert_eq!(lru.capacity(), 3);
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), [2, 1, 0]);
+
+    // an existing key is updated and promoted, not duplicated
+    lru.extend(vec![(0, 100), (3, 3)]);
+    assert_eq!(lru.iter().copied().collect::<Vec<_>>(), [(3, 3), (0, 100), (2, 2)]);
+}
+
+#[test]
+fn into_iter_test() {
+    let mut lru = LruCache::<String, Vec<u8>>::new(3);
+    for k in ["a", "b", "c"] {
+        lru.put(String::from(k), k.as_bytes().to_vec());
+    }
+    lru.get("a");
+
+    let mut pairs = lru.into_iter();
+    assert_eq!(pairs.len(), 3);
+    assert_eq!(pairs.next(), Some((String::from("a"), b"a".to_vec())));
+    assert_eq!(pairs.size_hint(), (2, Some(2)));
+    assert_eq!(pairs.next_back(), Some((String::from("b"), b"b".to_vec())));
+    assert_eq!(pairs.collect::<Vec<_>>(), [(String::from("c"), b"c".to_vec())]);
+
+    assert_eq!(LruCache::<i32, i32>::new(2).into_iter().next(), None);
+}
+
+#[test]
+fn touch_test() {
+    let mut lru = LruCache::<i32, i32>::new(3);
+    lru.extend((0..3).map(|k| (k, k)));
+    assert!(lru.touch(&0));
+    assert!(!lru.touch(&7));
+    assert_eq!(lru.keys().copied().collect::<Vec<_>>(), [0, 2, 1]);
+
+    // the touched key survives, the next coldest goes
+    assert_eq!(lru.put(3, 3), Some((1, 1)));
+    assert_eq!((lru.hits(), lru.misses()), (1, 1));
+}
+
+#[test]
+fn debug_test() {
+    let mut lru = LruCache::<&'static str, i32>::new(3);
+    lru.put("a", 1);
+    lru.put("b", 2);
+    assert_eq!(
+        format!("{:?}", lru),
+        r#"LruCache { capacity: 3, len: 2, entries: {"b": 2, "a": 1} }"#
+    );
+
+    let big = LruCache::from_iter_with_capacity(100, (0..100).map(|k| (k, k)));
+    let short = format!("{:?}", big);
+    assert!(short.starts_with("LruCache { capacity: 100, len: 100, entries: {99: 99, 98: 98,"));
+    assert!(short.ends_with("68: 68}, .. }"));
+    assert!(format!("{:#?}", big).contains("    0: 0,\n"));
+}
+
+#[test]
+fn zero_size_test() {
+    use std::sync::{Arc, Mutex};
+
+    let mut lru = LruCache::<String, i32>::new(0);
+    assert_eq!(lru.put(String::from("a"), 1), Some((String::from("a"), 1)));
+    assert_eq!(lru.put_with_ttl(String::from("b"), 2, Duration::from_secs(1)), Some((String::from("b"), 2)));
+    assert_eq!(lru.len(), 0);
+    assert_eq!(lru.get("a"), None);
+    assert_eq!(lru.peek("a"), None);
+    assert!(!lru.touch("a"));
+    assert_eq!(lru.pop_lru(), None);
+    assert_eq!((lru.hits(), lru.misses(), lru.insertions()), (0, 3, 0));
+
+    let bounced = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&bounced);
+    let mut lru = LruCache::with_eviction_handler(0, move |k, v| sink.lock().unwrap().push((k, v)));
+    assert_eq!(lru.put(1, 10), None);
+    assert_eq!(*bounced.lock().unwrap(), [(1, 10)]);
+    assert!(lru.is_empty());
+
+    let mut slru = SlruCache::<i32, i32>::new(0, 0);
+    assert_eq!(slru.put(1, 10), Some((1, 10)));
+    assert_eq!(slru.get(&1), None);
+    assert!(slru.is_empty());
+
+    let concurrent = ConcurrentLruCache::<i32, i32>::with_shards(0, 4);
+    assert_eq!(concurrent.put(1, 10), Some((1, 10)));
+    assert_eq!(concurrent.get(&1), None);
+    assert_eq!(concurrent.len(), 0);
+}
+
+#[test]
+fn no_key_clones_test() {
+    use std::cell::Cell;
+
+    thread_local!(static CLONES: Cell<usize> = const { Cell::new(0) });
+
+    #[derive(PartialEq, Eq, Hash, Debug)]
+    struct CountedKey(String);
+
+    impl Clone for CountedKey {
+        fn clone(&self) -> Self {
+            CLONES.with(|c| c.set(c.get() + 1));
+            CountedKey(self.0.clone())
+        }
+    }
+
+    let key = |k: i32| CountedKey(k.to_string());
+    let mut lru = LruCache::new(8);
+    for k in 0..20 {
+        lru.put(key(k), k);
+        lru.put(key(k), k + 1);
+    }
+    for k in 12..20 {
+        assert_eq!(lru.get(&key(k)), Some(&mut (k + 1)));
+        assert!(lru.touch(&key(k)));
+        assert_eq!(lru.peek(&key(k)), Some(&(k + 1)));
+    }
+    lru.entry(key(100)).or_insert(0);
+    assert_eq!(lru.remove(&key(13)), Some(14));
+    lru.retain(|k, _| k.0 != "14");
+    assert_eq!(lru.pop_lru().map(|(_, v)| v), Some(16));
+    assert_eq!(lru.resize(2).len(), 3);
+    assert_eq!(CLONES.with(Cell::get), 0);
+}
+
+#[test]
+fn key_index_test() {
+    // lots of churn so clusters wrap and get shifted around
+    let mut lru = LruCache::<u32, u32>::new(100);
+    for k in 0..5000u32 {
+        let k = k.wrapping_mul(2654435761) % 1000;
+        match k % 3 {
+            0 => {
+                lru.remove(&k);
+            }
+            _ => {
+                lru.put(k, k);
+            }
+        }
+        assert_eq!(lru.key_table.len, lru.len());
+    }
+    for (node_ptr, (key, val)) in lru.list.iter_ptr() {
+        assert_eq!(lru.find(key), Some(node_ptr));
+        assert_eq!(key, val);
+    }
+    for k in 0..1000 {
+        assert_eq!(lru.contains_key(&k), lru.iter().any(|(key, _)| *key == k));
+    }
+}
+
+#[test]
+fn key_index_collision_test() {
+    // 6 pointers in 8 slots, all homed at the end of the table so the cluster wraps around:
+    // slots 6, 7, 0, 1, 2, 3 hold pointers 0..6
+    let hashes = [6, 14, 22, 7, 15, 8];
+    let mut state = 7u32;
+    for _ in 0..200 {
+        let mut table = KeyIndex::new();
+        for (node_ptr, &hash) in hashes.iter().enumerate() {
+            table.insert(hash, node_ptr as u32);
+        }
+        assert_eq!(table.slots.len(), 8);
+
+        // remove every pointer in a shuffled order, the rest must stay reachable each time
+        let mut order = (0..hashes.len() as u32).collect::<Vec<_>>();
+        for i in (1..order.len()).rev() {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            order.swap(i, (state >> 16) as usize % (i + 1));
+        }
+        for (removed, &node_ptr) in order.iter().enumerate() {
+            table.remove(hashes[node_ptr as usize], node_ptr);
+            assert_eq!(table.len, hashes.len() - removed - 1);
+            for (other, &hash) in hashes.iter().enumerate() {
+                let other = other as u32;
+                let expected = if order[..=removed].contains(&other) { None } else { Some(other) };
+                assert_eq!(table.find(hash, |p| p == other), expected);
+            }
+        }
+        assert!(table.slots.iter().all(|&(_, p)| p == EMPTY));
+    }
+
+    // the 7th pointer would take the load past 3/4
+    let mut table = KeyIndex::new();
+    (0..7).for_each(|node_ptr| table.insert(6, node_ptr));
+    assert_eq!(table.slots.len(), 16);
+    assert!((0..7).all(|node_ptr| table.find(6, |p| p == node_ptr) == Some(node_ptr)));
+}
+
+#[test]
+fn peek_lru_mru_test() {
+    let mut lru = LruCache::<&str, i32>::new(3);
+    assert_eq!(lru.peek_lru(), None);
+    assert_eq!(lru.peek_mru(), None);
+
+    lru.put("a", 1);
+    assert_eq!(lru.peek_lru(), Some((&"a", &1)));
+    assert_eq!(lru.peek_mru(), Some((&"a", &1)));
+
+    lru.put("b", 2);
+    lru.put("c", 3);
+    assert_eq!(lru.peek_lru(), Some((&"a", &1)));
+    assert_eq!(lru.peek_mru(), Some((&"c", &3)));
+
+    // peeking doesn't promote, so the LRU is still the one evicted
+    assert_eq!(lru.peek_lru(), Some((&"a", &1)));
+    assert_eq!(lru.put("d", 4), Some(("a", 1)));
+    assert_eq!(lru.peek_lru(), Some((&"b", &2)));
+    assert_eq!((lru.hits(), lru.misses()), (0, 0));
+}
@@ -37,6 +37,18 @@ pub struct NaryForest<T> {
     pub memory: Vec<NaryNode<T>>,
 }
 
+impl<T> Default for NaryNode<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Default for NaryForest<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> NaryForest<T>
 where
     Self: Index<u32, Output = NaryNode<T>> + IndexMut<u32>,
@@ -89,6 +89,12 @@ impl<T> NaryTree<T> {
     }
 }
 
+impl<T> Default for NaryTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> ops::Index<NodeAddr> for NaryTree<T> {
     type Output = NaryNode<T>;
     fn index(&self, index: NodeAddr) -> &Self::Output {
@@ -1,12 +1,46 @@
 pub const FRONT: usize = 0;
 pub const REAR: usize = 1;
 
-use std::{ops, usize};
+use std::{
+    cell::UnsafeCell,
+    cmp, fmt, io,
+    iter::FromIterator,
+    ops,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, MutexGuard,
+    },
+    time::Duration,
+};
 
 struct IncrementQuery {
     old_ptr: usize,
     _cur_ptr: usize,
 }
+
+/// # Description
+/// Occupancy counters kept by every `RingBuffer`
+#[derive(Copy, Clone)]
+struct RingStats {
+    high_watermark: usize,
+    total_enqueued: u64,
+    total_dequeued: u64,
+}
+
+impl RingStats {
+    const fn starting_at(len: usize) -> Self {
+        Self {
+            high_watermark: len,
+            total_enqueued: 0,
+            total_dequeued: 0,
+        }
+    }
+}
+/// # Description
+/// Returned by `RingBuffer::shrink_to(..)` when the queued items wouldn't fit
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CannotShrink;
+
 /// # Descirption
 /// A fixed-capacity ring buffer
 pub struct RingBuffer<Memory> {
@@ -14,36 +48,137 @@ pub struct RingBuffer<Memory> {
     capacity: usize,
     pointers: [usize; 2],
     memory: Memory,
+    stats: RingStats,
+    /// absolute sequence number of the front item, this is what `Cursor`s are measured against
+    head_seq: u64,
+}
+
+/// # Description
+/// Anything a `RingBuffer` can live on top of
+/// # Comments
+/// The capacity of a ring always comes from the length of its memory
+pub trait RingMemory {
+    type Item;
+    fn as_slice(&self) -> &[Self::Item];
+    fn as_mut_slice(&mut self) -> &mut [Self::Item];
+}
+
+impl<T> RingMemory for Vec<T> {
+    type Item = T;
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> RingMemory for [T; N] {
+    type Item = T;
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> RingMemory for &mut [T] {
+    type Item = T;
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
 }
 
 impl<T> RingBuffer<T>
 where
-    T: Default,
+    T: RingMemory + Default,
 {
+    /// # Description
+    /// Creates an empty ring on top of `T::default()`
+    /// # Comments
+    /// - `RingBuffer<Vec<_>>` starts with zero capacity
+    /// - `RingBuffer<[_; N]>` starts with a capacity of `N` and never allocates
     pub fn new() -> Self {
+        let memory = T::default();
+        Self {
+            len: 0,
+            capacity: memory.as_slice().len(),
+            pointers: [0; 2],
+            memory,
+            stats: RingStats::starting_at(0),
+            head_seq: 0,
+        }
+    }
+}
+
+impl<T> Default for RingBuffer<T>
+where
+    T: RingMemory + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> RingBuffer<[T; N]> {
+    /// # Description
+    /// Creates an empty ring on top of `memory` with a capacity of `N`
+    /// # Comments
+    /// - This works for any `N` (std only implements `Default` for arrays of up to 32 items)
+    /// - This is a `const fn` so fixed rings can be built in a `static`
+    pub const fn from_array(memory: [T; N]) -> Self {
         Self {
             len: 0,
-            capacity: 0,
+            capacity: N,
             pointers: [0; 2],
-            memory: T::default(),
+            memory,
+            stats: RingStats::starting_at(0),
+            head_seq: 0,
         }
     }
 }
 
+impl<'a, T> RingBuffer<&'a mut [T]> {
+    /// # Description
+    /// Creates an empty ring on top of borrowed `memory` with a capacity of `memory.len()`
+    /// # Comments
+    /// - No allocation ever takes place, so `memory` can be a chunk of an arena, a mapped buffer, etc
+    /// - The ring borrows `memory` for its whole life so it can never outlive it
+    pub fn from_slice(memory: &'a mut [T]) -> Self {
+        Self::from(RingSpecifier::MakeEmpty(memory))
+    }
+}
+
 impl<T> RingBuffer<T> {
     pub fn len(&self) -> usize {
         self.len
     }
 
+    /// # Description
+    /// Empties the ring by resetting the pointers
+    /// # Comments
+    /// The items themselves are not dropped, they linger in memory until overwritten.
+    /// Use `clear_drop()` when the items own resources
     pub fn clear(&mut self) {
+        self.record(0, self.len);
+        self.head_seq += self.len as u64;
         self.len = 0;
         self.pointers = [0; 2];
     }
 
     pub fn is_empty(&self) -> bool {
-        self.len <= 0
+        self.len == 0
     }
 
+    /// # Description
+    /// returns true when there is no room to enqueue another item
+    /// # Comments
+    /// A zero-capacity ring (what `RingBuffer::<Vec<_>>::new()` gives you) is both empty and full:
+    /// there is nothing to dequeue and nowhere to enqueue
     pub fn is_full(&self) -> bool {
         self.len >= self.capacity
     }
@@ -56,6 +191,41 @@ impl<T> RingBuffer<T> {
         self.pointers[REAR]
     }
 
+    /// # Description
+    /// returns the largest `len` this ring has reached since it was built (or since `reset_stats()`)
+    pub fn high_watermark(&self) -> usize {
+        self.stats.high_watermark
+    }
+
+    /// # Description
+    /// returns how many items have entered the ring
+    pub fn total_enqueued(&self) -> u64 {
+        self.stats.total_enqueued
+    }
+
+    /// # Description
+    /// returns how many items have left the ring
+    /// # Comments
+    /// Every way out counts (dequeue, pop_rear, overwrite, clear, retain ...) so
+    /// `total_enqueued() - total_dequeued() == len()` until the stats get reset
+    pub fn total_dequeued(&self) -> u64 {
+        self.stats.total_dequeued
+    }
+
+    /// # Description
+    /// zeroes the counters and restarts the high watermark from the current `len`
+    pub fn reset_stats(&mut self) {
+        self.stats = RingStats::starting_at(self.len);
+    }
+
+    /// # Description
+    /// bumps the stats after `len` changed, the overhead is just a couple of adds and a max
+    fn record(&mut self, enqueued: usize, dequeued: usize) {
+        self.stats.total_enqueued += enqueued as u64;
+        self.stats.total_dequeued += dequeued as u64;
+        self.stats.high_watermark = self.stats.high_watermark.max(self.len);
+    }
+
     /// # Description
     /// Makes room for newly enqueued item and retuns location of newly allocated index
     /// # returns
@@ -78,11 +248,12 @@ impl<T> RingBuffer<T> {
     /// # Returns
     /// `None` is pop fails
     pub fn pop_rear(&mut self) -> Option<usize> {
-        if self.is_empty() {
+        if self.is_empty() || self.capacity == 0 {
             None
         } else {
             self.pointers[REAR] = (self.pointers[REAR] + self.capacity - 1) % self.capacity;
             self.len -= 1;
+            self.record(0, 1);
             Some(self.pointers[REAR])
         }
     }
@@ -100,12 +271,22 @@ impl<T> RingBuffer<T> {
     where
         CB: Fn(&Self) -> bool,
     {
-        if has_no_space(self) {
+        // capacity 0 is checked separately so the `% capacity` below can never divide by zero,
+        // whatever `has_no_space` says
+        if has_no_space(self) || self.capacity == 0 {
             None
         } else {
             let old_ptr = self.pointers[pointer_type];
             self.pointers[pointer_type] = (self.pointers[pointer_type] + 1) % self.capacity;
             self.len = ((self.len as isize) + len_inc_dec) as usize;
+            if pointer_type == FRONT {
+                self.head_seq += 1;
+            }
+            if len_inc_dec > 0 {
+                self.record(len_inc_dec as usize, 0);
+            } else {
+                self.record(0, -len_inc_dec as usize);
+            }
             Some(IncrementQuery {
                 old_ptr,
                 _cur_ptr: self.pointers[pointer_type],
@@ -113,16 +294,89 @@ impl<T> RingBuffer<T> {
         }
     }
 
+    /// # Description
+    /// Dequeues `n` items at once by moving the front pointer, the items themselves aren't touched
+    /// # Comments
+    /// Pair this with `contiguous_front()` for zero-copy consumption
+    /// # Panics
+    /// if `n > len`
+    pub fn advance_front(&mut self, n: usize) {
+        assert!(
+            n <= self.len,
+            "cannot advance the front by {} items, the ring only holds {}",
+            n,
+            self.len
+        );
+        if n == 0 {
+            return;
+        }
+        self.pointers[FRONT] = (self.pointers[FRONT] + n) % self.capacity;
+        self.len -= n;
+        self.head_seq += n as u64;
+        self.record(0, n);
+    }
+
     /// # Description
     /// retuns index into the next item
     pub fn peek_next(&self) -> Option<usize> {
-        if self.len <= 1 {
+        if self.len <= 1 || self.capacity == 0 {
             None
         } else {
             Some((self.pointers[FRONT] + 1) % self.capacity)
         }
     }
 
+    /// # Description
+    /// returns index of the most recently enqueued item
+    /// # Comments
+    /// `pointers[REAR]` points one past the last item so it has to be stepped back (with wrap-around)
+    fn last_index(&self) -> Option<usize> {
+        if self.is_empty() {
+            None
+        } else {
+            Some((self.pointers[REAR] + self.capacity - 1) % self.capacity)
+        }
+    }
+
+    /// # Description
+    /// translates a logical offset from the front into an index into memory
+    /// # Returns
+    /// `None` if `logical >= len`
+    fn physical_index(&self, logical: usize) -> Option<usize> {
+        if logical < self.len {
+            Some((self.pointers[FRONT] + logical) % self.capacity)
+        } else {
+            None
+        }
+    }
+
+    /// # Description
+    /// returns the (at most two) ranges of memory the queued items live in
+    /// # Comments
+    /// The first range starts at the front pointer, the second range covers the part of the
+    /// queue that wrapped around to the start of memory (and is empty if nothing wrapped)
+    fn live_ranges(&self) -> (ops::Range<usize>, ops::Range<usize>) {
+        let front = self.pointers[FRONT];
+        let end = front + self.len;
+        if end <= self.capacity {
+            (front..end, 0..0)
+        } else {
+            (front..self.capacity, 0..end - self.capacity)
+        }
+    }
+
+    /// # Description
+    /// returns the (at most two) ranges of memory that are free to enqueue into, rear first
+    fn free_ranges(&self) -> (ops::Range<usize>, ops::Range<usize>) {
+        let rear = self.pointers[REAR];
+        let end = rear + (self.capacity - self.len);
+        if end <= self.capacity {
+            (rear..end, 0..0)
+        } else {
+            (rear..self.capacity, 0..end - self.capacity)
+        }
+    }
+
     fn index_iter(&self) -> RingIter {
         RingIter {
             cur: self.pointers[FRONT],
@@ -134,206 +388,3534 @@ impl<T> RingBuffer<T> {
 
 impl<T> RingBuffer<Vec<T>>
 where
-    T: Sized + Default + Clone,
+    T: Default,
 {
     /// # Description
     /// Sets ring buffers capacity to `cap`
     /// # Comments
     /// Calling this may result in an allocation
-    pub fn with_capacity(mut self, cap: usize) -> Self {
+    pub fn with_capacity(self, cap: usize) -> Self {
+        self.with_capacity_init(cap, T::default)
+    }
+}
+
+impl<T> RingBuffer<Vec<T>> {
+    /// # Description
+    /// Same as `with_capacity(..)` but empty slots are filled by calling `init`
+    /// # Comments
+    /// Use this for types that have no sensible `Default`, `init` only produces placeholders
+    /// and those never show up in `iter()`/`get(..)` until something is enqueued over them
+    pub fn with_capacity_init<F>(mut self, cap: usize, init: F) -> Self
+    where
+        F: FnMut() -> T,
+    {
         self.capacity = cap;
-        self.memory.resize(cap, T::default());
+        self.memory.resize_with(cap, init);
         self
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.index_iter().map(move |i| &self.memory[i])
+    /// # Description
+    /// Builds a full ring of capacity `cap` where slot `i` holds `f(i)`
+    /// # Comments
+    /// `iter()` visits the items in the order `f` produced them
+    pub fn fill_with<F>(cap: usize, f: F) -> Self
+    where
+        F: FnMut(usize) -> T,
+    {
+        Self::from(RingSpecifier::MakeFull((0..cap).map(f).collect()))
     }
 
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
-        self.index_iter()
-            .map(move |i| unsafe { &mut *self.memory.as_mut_ptr().offset(i as isize) })
-    }
-}
-/// # Description
-/// Use this enum create and initalize ring buffers to various sizes
-pub enum RingSpecifier<Memory> {
     /// # Description
-    /// used to create and empty queue with the `Memory`'s length being the capacity
+    /// Shrinks the capacity down to `new_cap` and gives the spare memory back to the allocator
     /// # Comments
-    /// basically it enqueues nothing in the vector and the capacity of the ring
-    /// comes from the vector's current length
-    MakeEmpty(Memory),
+    /// The queued items are moved (in logical order) to the start of memory first, so a wrapped
+    /// ring comes out with `front == 0`. Asking for more than the current capacity does nothing
+    /// # Returns
+    /// `Err(CannotShrink)` if `new_cap < len`, the ring is left untouched
+    pub fn shrink_to(&mut self, new_cap: usize) -> Result<(), CannotShrink> {
+        if new_cap < self.len {
+            return Err(CannotShrink);
+        }
+        if new_cap >= self.capacity {
+            return Ok(());
+        }
+        self.make_contiguous();
+        self.memory.truncate(new_cap);
+        self.memory.shrink_to_fit();
+        self.capacity = new_cap;
+        self.pointers[REAR] = if new_cap == 0 { 0 } else { self.len % new_cap };
+        Ok(())
+    }
 
     /// # Description
-    /// used to create and empty queue with the `Memory`'s length being the capacity.
+    /// Consumes the ring and returns the queued items front to rear, without cloning
     /// # Comments
-    /// basically it enqueues everything in the `Memory` and the capacity of the ring
-    /// comes from the `Memory`'s current length
-    MakeFull(Memory),
+    /// Memory is rotated into logical order and truncated to `len`, so this reuses the allocation
+    pub fn into_vec(mut self) -> Vec<T> {
+        self.make_contiguous();
+        self.memory.truncate(self.len);
+        self.memory
+    }
+
+    /// # Description
+    /// Shrinks the capacity down to `len`, leaving a full ring
+    pub fn shrink_to_fit(&mut self) {
+        // can't fail, `len <= len`
+        let _ = self.shrink_to(self.len);
+    }
 }
 
-impl<T> From<RingSpecifier<Vec<T>>> for RingBuffer<Vec<T>> {
-    fn from(spec: RingSpecifier<Vec<T>>) -> Self {
-        match spec {
-            RingSpecifier::MakeEmpty(mem) => Self {
-                len: 0,
-                pointers: [0, 0],
-                capacity: mem.len(),
-                memory: mem,
-            },
-            RingSpecifier::MakeFull(mem) => Self {
-                len: mem.len(),
-                pointers: [0, 0],
-                capacity: mem.len(),
-                memory: mem,
-            },
+impl<T, M> RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+{
+    fn slots(&self) -> &[T] {
+        self.memory.as_slice()
+    }
+
+    fn slots_mut(&mut self) -> &mut [T] {
+        self.memory.as_mut_slice()
+    }
+
+    /// # Description
+    /// walks the queued items front to rear, use `.rev()` to walk rear to front
+    pub fn iter<'a>(&'a self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator
+    where
+        T: 'a,
+    {
+        self.index_iter().map(move |i| &self.slots()[i])
+    }
+
+    /// # Description
+    /// walks the queued items rear to front (newest first)
+    pub fn iter_rev<'a>(&'a self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator
+    where
+        T: 'a,
+    {
+        self.iter().rev()
+    }
+
+    /// # Description
+    /// mutable version of `iter()`
+    /// # Comments
+    /// Walks the two halves from `as_mut_slices()` one after the other, so every `&mut T` comes
+    /// from a disjoint part of memory
+    pub fn iter_mut<'a>(
+        &'a mut self,
+    ) -> impl DoubleEndedIterator<Item = &'a mut T> + ExactSizeIterator
+    where
+        T: 'a,
+    {
+        let (first, second) = self.as_mut_slices();
+        RingIterMut {
+            first: first.iter_mut(),
+            second: second.iter_mut(),
         }
     }
-}
 
-impl<T> ops::Index<Option<usize>> for RingBuffer<Vec<T>> {
-    type Output = T;
-    fn index(&self, index: Option<usize>) -> &Self::Output {
-        index.map(|a| self.memory.get(a)).flatten().unwrap()
+    /// # Description
+    /// Enqueues `value` by moving it directly into the newly allocated slot
+    /// # Returns
+    /// index of the slot `value` was written to \
+    /// `None` if enqueue fails (`value` gets dropped)
+    pub fn enqueue_value(&mut self, value: T) -> Option<usize> {
+        self.try_enqueue(value).ok()
     }
-}
-impl<T> ops::IndexMut<Option<usize>> for RingBuffer<Vec<T>> {
-    fn index_mut(&mut self, index: Option<usize>) -> &mut Self::Output {
-        index
-            .map(move |a| self.memory.get_mut(a))
-            .flatten()
-            .unwrap()
+
+    /// # Description
+    /// Resets the ring to full, overwriting slot `i` with `f(i)` and reusing the memory
+    /// # Comments
+    /// The pointers end up in the canonical full state (`front == rear == 0`, `len == capacity`)
+    pub fn refill_with<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize) -> T,
+    {
+        self.clear();
+        for (i, slot) in self.slots_mut().iter_mut().enumerate() {
+            *slot = f(i);
+        }
+        self.len = self.capacity;
+        self.record(self.capacity, 0);
     }
-}
 
-impl<T> ops::Index<usize> for RingBuffer<Vec<T>> {
-    type Output = T;
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.memory[index]
+    /// # Description
+    /// Same as `enqueue_value(..)` but a full ring hands `value` back instead of dropping it
+    /// # Comments
+    /// Handy for back-pressure: the caller can retry later, spill somewhere else or log it
+    /// # Returns
+    /// index of the slot `value` was written to \
+    /// `Err(value)` if the ring is full
+    pub fn try_enqueue(&mut self, value: T) -> Result<usize, T> {
+        match self.enqueue() {
+            Some(idx) => {
+                self.slots_mut()[idx] = value;
+                Ok(idx)
+            }
+            None => Err(value),
+        }
     }
-}
 
-impl<T> ops::IndexMut<usize> for RingBuffer<Vec<T>> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.memory[index]
+    /// # Description
+    /// Dequeues the front item and moves it out of the ring, leaving `filler` in the vacated slot
+    /// # Comments
+    /// This is `dequeue_value()` for types that don't implement `Default`
+    /// # Returns
+    /// `None` if dequeue fails (`filler` gets dropped)
+    pub fn dequeue_replace(&mut self, filler: T) -> Option<T> {
+        let idx = self.dequeue()?;
+        Some(std::mem::replace(&mut self.slots_mut()[idx], filler))
     }
-}
 
-pub struct RingIter {
-    cur: usize,
-    cap: usize,
-    len: usize,
-}
+    /// # Description
+    /// returns a reference to the front item (the next item to be dequeued)
+    /// # Returns
+    /// `None` if the ring is empty
+    pub fn peek_front(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(&self.slots()[self.pointers[FRONT]])
+        }
+    }
 
-impl Iterator for RingIter {
-    type Item = usize;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.len == 0 {
+    pub fn peek_front_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
             None
         } else {
-            let old_cur = self.cur;
-            self.len -= 1;
-            self.cur = (self.cur + 1) % self.cap;
-            Some(old_cur)
+            let front = self.pointers[FRONT];
+            Some(&mut self.slots_mut()[front])
         }
     }
-}
 
-#[test]
-fn ring_buffer_base_cases() {
-    let rb: RingBuffer<Vec<i32>> = RingBuffer::new();
+    /// # Description
+    /// returns a reference to the rear item (the most recently enqueued item)
+    /// # Returns
+    /// `None` if the ring is empty
+    pub fn peek_rear(&self) -> Option<&T> {
+        self.last_index().map(move |i| &self.slots()[i])
+    }
 
-    assert_eq!(rb.iter().map(|&a| a).collect::<Vec<_>>(), vec![]);
-    assert_eq!(rb.is_empty(), true);
-    assert_eq!(rb.is_full(), true);
+    pub fn peek_rear_mut(&mut self) -> Option<&mut T> {
+        self.last_index().map(move |i| &mut self.slots_mut()[i])
+    }
 
-    let rb = RingBuffer::from(RingSpecifier::MakeFull(vec![0]));
-    assert_eq!(rb.iter().map(|&a| a).collect::<Vec<_>>(), vec![0]);
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), true);
-}
+    /// # Description
+    /// returns the item `i` places behind the front, so `get(0)` is the next item to be dequeued
+    /// # Comments
+    /// Unlike `rb[i]` (which indexes raw memory), this stays correct after the ring wraps
+    /// # Returns
+    /// `None` if `i >= len`
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.physical_index(i).map(move |i| &self.slots()[i])
+    }
 
-#[test]
-fn ring_buffer_deq_tests() {
-    let mut rb = RingBuffer::from(RingSpecifier::MakeFull(vec![1, 2, 3, 4, 5, 6, 7]));
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        self.physical_index(i)
+            .map(move |i| &mut self.slots_mut()[i])
+    }
 
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), true);
-    assert_eq!(
-        rb.iter().map(|&a| a).collect::<Vec<_>>(),
-        vec![1, 2, 3, 4, 5, 6, 7]
-    );
+    /// # Description
+    /// returns the logical index (measured from the front) of the first item satisfying `pred`
+    /// # Comments
+    /// The index works with `get(..)`, so `rb.get(rb.position(p)?)` is the matched item
+    pub fn position<F>(&self, pred: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().position(pred)
+    }
 
-    let decd_item = rb.dequeue();
-    assert_eq!(rb[decd_item], 1);
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), false);
-    assert_eq!(
-        rb.iter().map(|&a| a).collect::<Vec<_>>(),
-        vec![2, 3, 4, 5, 6, 7]
-    );
+    /// # Description
+    /// returns true if any queued item equals `x`
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|item| item == x)
+    }
+
+    /// # Description
+    /// Reads the ring as a delay line, `delay` samples back from the most recent one (the rear)
+    /// # Comments
+    /// A delay of `0.0` is the most recent sample, `1.0` the one before it and so on. Fractional
+    /// delays are interpolated linearly between the two neighbouring samples
+    /// # Returns
+    /// `None` if `delay` is negative, NaN or reaches further back than the oldest sample
+    pub fn read_delayed(&self, delay: f32) -> Option<f32>
+    where
+        T: Copy + Into<f32>,
+    {
+        self.read_delayed_with(delay, |&newer, &older, t| {
+            let (newer, older): (f32, f32) = (newer.into(), older.into());
+            newer + (older - newer) * t
+        })
+    }
+
+    /// # Description
+    /// Same as `read_delayed(..)` but `interpolate(newer, older, t)` blends the two neighbouring
+    /// samples, `t` is the fractional part of `delay` in `[0, 1)`
+    pub fn read_delayed_with<F, R>(&self, delay: f32, interpolate: F) -> Option<R>
+    where
+        F: FnOnce(&T, &T, f32) -> R,
+    {
+        if delay.is_nan() || delay < 0.0 || delay > (self.len as f32 - 1.0) {
+            return None;
+        }
+        let whole = delay.floor() as usize;
+        let t = delay - whole as f32;
+        let newer = self.get(self.len - 1 - whole)?;
+        // an exact delay onto the oldest sample has no older neighbour to blend with
+        let older = self.len.checked_sub(2 + whole).and_then(|i| self.get(i));
+        Some(interpolate(newer, older.unwrap_or(newer), t))
+    }
+
+    /// # Description
+    /// Creates a read cursor positioned at the front, so it sees every item currently queued
+    /// plus everything enqueued afterwards
+    /// # Comments
+    /// Any number of cursors can read the same ring (broadcast), each one only moves itself.
+    /// Cursors don't hold items back, `dequeue()`/`push_overwrite(..)` are free to drop items a
+    /// slow cursor hasn't read yet. Use `lag(..)` to see how far behind a cursor is
+    pub fn cursor(&self) -> Cursor {
+        Cursor {
+            next: self.head_seq,
+        }
+    }
+
+    /// # Description
+    /// Reads the next item for `cursor` and advances only that cursor
+    /// # Comments
+    /// - Named `read_cursor` so it doesn't shadow `io::Read::read` on byte rings
+    /// - When the writer has lapped the cursor (the items it was due to read are gone) the cursor
+    ///   jumps forward to the current front and `Overrun` reports how many items it missed
+    /// - `pop_rear`, `retain` and the rotations rewrite the queue, cursors just follow the
+    ///   current logical order
+    /// # Returns
+    /// `Ok(None)` once the cursor has caught up with the rear
+    pub fn read_cursor(&self, cursor: &mut Cursor) -> Result<Option<&T>, Overrun> {
+        if cursor.next < self.head_seq {
+            let missed = self.head_seq - cursor.next;
+            cursor.next = self.head_seq;
+            return Err(Overrun { missed });
+        }
+        let logical = (cursor.next - self.head_seq) as usize;
+        match self.get(logical) {
+            Some(item) => {
+                cursor.next += 1;
+                Ok(Some(item))
+            }
+            None => {
+                cursor.next = self.head_seq + self.len as u64;
+                Ok(None)
+            }
+        }
+    }
+
+    /// # Description
+    /// returns how many items `cursor` still has to read
+    /// # Comments
+    /// A lag greater than `len()` means the cursor has been overrun, its next `read_cursor(..)`
+    /// will report the missed items
+    pub fn lag(&self, cursor: &Cursor) -> u64 {
+        (self.head_seq + self.len as u64).saturating_sub(cursor.next)
+    }
+
+    /// # Description
+    /// Non-panicking version of `rb[idx]`, meant for the index returned by `enqueue()`/`dequeue()`
+    /// # Comments
+    /// `idx` is a raw memory index, just like `rb[idx]`
+    /// # Returns
+    /// `None` if `idx` is `None` (the enqueue/dequeue failed) or out of bounds
+    pub fn try_get(&self, idx: Option<usize>) -> Option<&T> {
+        idx.and_then(move |i| self.slots().get(i))
+    }
+
+    pub fn try_get_mut(&mut self, idx: Option<usize>) -> Option<&mut T> {
+        idx.and_then(move |i| self.slots_mut().get_mut(i))
+    }
+
+    /// # Description
+    /// Binary searches a ring whose items are sorted front to rear, same contract as
+    /// `slice::binary_search_by`
+    /// # Returns
+    /// `Ok(i)` with the logical index (usable with `get(i)`) of a matching item \
+    /// `Err(i)` with the logical index where a matching item could be inserted to keep it sorted
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> cmp::Ordering,
+    {
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let physical = (self.pointers[FRONT] + mid) % self.capacity;
+            match f(&self.slots()[physical]) {
+                cmp::Ordering::Less => lo = mid + 1,
+                cmp::Ordering::Greater => hi = mid,
+                cmp::Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(lo)
+    }
+
+    /// # Description
+    /// clones the queued items into a `Vec`, front to rear
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let (first, second) = self.as_slices();
+        let mut items = Vec::with_capacity(self.len);
+        items.extend_from_slice(first);
+        items.extend_from_slice(second);
+        items
+    }
+
+    /// # Description
+    /// `enqueue_slice(..)` for types that are `Clone` but not `Copy`
+    /// # Comments
+    /// Items are cloned straight into the free slots (at most two runs), live items are never
+    /// overwritten
+    /// # Returns
+    /// the number of items cloned in, `0` if the ring is full
+    pub fn extend_from_slice(&mut self, src: &[T]) -> usize
+    where
+        T: Clone,
+    {
+        let (first, second) = self.free_ranges();
+        let n = src.len().min(first.len() + second.len());
+        if n == 0 {
+            return 0;
+        }
+        let split = n.min(first.len());
+        let slots = self.slots_mut();
+        slots[first.start..first.start + split].clone_from_slice(&src[..split]);
+        slots[second.start..second.start + (n - split)].clone_from_slice(&src[split..n]);
+        self.pointers[REAR] = (self.pointers[REAR] + n) % self.capacity;
+        self.len += n;
+        self.record(n, 0);
+        n
+    }
+
+    /// # Description
+    /// returns the queued items as two contiguous slices (front to rear)
+    /// # Comments
+    /// The second slice holds the items that wrapped around the end of memory, it's empty
+    /// when the items are already contiguous
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (first, second) = self.live_ranges();
+        (&self.slots()[first], &self.slots()[second])
+    }
+
+    /// # Description
+    /// returns the longest contiguous run of items at the front, it ends at the wrap point or
+    /// the rear (whichever comes first)
+    /// # Comments
+    /// A zero-copy read loop looks like: consume part of `contiguous_front()`, then
+    /// `advance_front(consumed)`
+    pub fn contiguous_front(&self) -> &[T] {
+        self.as_slices().0
+    }
+
+    /// # Description
+    /// mutable version of `as_slices()`
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (first, second) = self.live_ranges();
+        let (head, tail) = self.slots_mut().split_at_mut(first.start);
+        (&mut tail[..first.len()], &mut head[second])
+    }
+
+    /// # Description
+    /// Slides a window of `size` items over the queue (front to rear) without copying anything out
+    /// # Comments
+    /// A window that straddles the wrap point comes back split in two, so each item is a pair
+    /// of slices that should be read back to back (the second one is empty most of the time).
+    /// Nothing is yielded when `size > len`
+    /// # Panics
+    /// if `size` is 0
+    pub fn windows(&self, size: usize) -> RingWindows<'_, T> {
+        assert!(size != 0, "window size must be non-zero");
+        RingWindows {
+            slices: self.as_slices(),
+            start: 0,
+            size,
+        }
+    }
+
+    /// # Description
+    /// Same as `windows(..)` but the runs don't overlap, the last chunk is shorter if `size`
+    /// doesn't evenly divide `len` (just like `slice::chunks`)
+    /// # Panics
+    /// if `size` is 0
+    pub fn chunks(&self, size: usize) -> RingChunks<'_, T> {
+        assert!(size != 0, "chunk size must be non-zero");
+        RingChunks {
+            slices: self.as_slices(),
+            start: 0,
+            size,
+        }
+    }
+
+    /// # Description
+    /// Rotates memory in place so the queued items occupy one contiguous run starting at index 0
+    /// # Returns
+    /// the queued items as one slice (front to rear)
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let front = self.pointers[FRONT];
+        self.slots_mut().rotate_left(front);
+        self.pointers[FRONT] = 0;
+        self.pointers[REAR] = if self.capacity == 0 {
+            0
+        } else {
+            self.len % self.capacity
+        };
+        let len = self.len;
+        &mut self.slots_mut()[..len]
+    }
+
+    /// # Description
+    /// Rotates the queue `n` places to the left, so the item at logical index `n` becomes the front
+    /// and the old front items end up at the rear (in order)
+    /// # Comments
+    /// - `n` is taken modulo `len`, so rotating by `len` does nothing
+    /// - When the ring is full this is O(1), only the pointers move. Otherwise every rotated
+    ///   item is swapped over the free gap, which is O(n)
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.is_empty() {
+            return;
+        }
+        let n = n % self.len;
+        if self.is_full() {
+            self.pointers[FRONT] = (self.pointers[FRONT] + n) % self.capacity;
+            self.pointers[REAR] = self.pointers[FRONT];
+            return;
+        }
+        for _ in 0..n {
+            let [front, rear] = self.pointers;
+            self.slots_mut().swap(front, rear);
+            self.pointers[FRONT] = (front + 1) % self.capacity;
+            self.pointers[REAR] = (rear + 1) % self.capacity;
+        }
+    }
+
+    /// # Description
+    /// Rotates the queue `n` places to the right, so the last `n` items move to the front
+    /// # Comments
+    /// Same rules as `rotate_left(..)`, O(1) when full and O(n) otherwise
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.is_empty() {
+            return;
+        }
+        let n = n % self.len;
+        if self.is_full() {
+            self.pointers[FRONT] = (self.pointers[FRONT] + self.capacity - n) % self.capacity;
+            self.pointers[REAR] = self.pointers[FRONT];
+            return;
+        }
+        for _ in 0..n {
+            let front = (self.pointers[FRONT] + self.capacity - 1) % self.capacity;
+            let rear = (self.pointers[REAR] + self.capacity - 1) % self.capacity;
+            self.slots_mut().swap(front, rear);
+            self.pointers = [front, rear];
+        }
+    }
+
+    /// # Description
+    /// Enqueues `value`, and if the ring is full the oldest item is dropped to make room for it
+    /// # Comments
+    /// This is the 'classic' circular buffer behaviour, handy for streaming telemetry/audio samples
+    /// # Returns
+    /// the overwritten item or `None` if there was still space \
+    /// A zero-capacity ring can't hold anything so `value` is handed straight back
+    pub fn push_overwrite(&mut self, value: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(value);
+        }
+        let evicted = if self.is_full() { self.dequeue() } else { None };
+        let idx = self.enqueue()?;
+        match evicted {
+            // when full, front and rear share a slot so the evicted item lives at `idx`
+            Some(_) => Some(std::mem::replace(&mut self.slots_mut()[idx], value)),
+            None => {
+                self.slots_mut()[idx] = value;
+                None
+            }
+        }
+    }
+
+    /// # Description
+    /// Enqueues items from `iter` until either the iterator runs out or the ring is full
+    /// # Comments
+    /// The iterator is never advanced past the last item that fits, so whatever is left
+    /// can still be pulled out of it by the caller
+    /// # Returns
+    /// the number of items that were actually enqueued
+    pub fn enqueue_iter<I>(&mut self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        let mut count = 0;
+        while !self.is_full() {
+            match iter.next() {
+                Some(value) => {
+                    self.enqueue_value(value);
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+        count
+    }
+}
+
+impl<T, M> RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+    T: Copy,
+{
+    /// # Description
+    /// Copies as much of `src` as fits behind the rear, using at most two `copy_from_slice` calls
+    /// # Comments
+    /// Much faster than enqueueing one item at a time since the rear pointer only moves once
+    /// # Returns
+    /// the number of items written, `0` if the ring is full, `src.len()` if everything fit
+    pub fn enqueue_slice(&mut self, src: &[T]) -> usize {
+        let (first, second) = self.free_ranges();
+        let n = src.len().min(first.len() + second.len());
+        if n == 0 {
+            return 0;
+        }
+        let split = n.min(first.len());
+        let slots = self.slots_mut();
+        slots[first.start..first.start + split].copy_from_slice(&src[..split]);
+        slots[second.start..second.start + (n - split)].copy_from_slice(&src[split..n]);
+        self.pointers[REAR] = (self.pointers[REAR] + n) % self.capacity;
+        self.len += n;
+        self.record(n, 0);
+        n
+    }
+
+    /// # Description
+    /// Copies up to `dst.len()` items off the front into `dst`, using at most two
+    /// `copy_from_slice` calls
+    /// # Comments
+    /// The copied items are dequeued, their slots are left as is since `T: Copy` owns nothing
+    /// # Returns
+    /// the number of items moved into `dst`, `0` if the ring is empty
+    pub fn dequeue_into(&mut self, dst: &mut [T]) -> usize {
+        let (first, second) = self.as_slices();
+        let n = dst.len().min(first.len() + second.len());
+        if n == 0 {
+            return 0;
+        }
+        let split = n.min(first.len());
+        dst[..split].copy_from_slice(&first[..split]);
+        dst[split..n].copy_from_slice(&second[..n - split]);
+        self.advance_front(n);
+        n
+    }
+}
+
+impl<T> FromIterator<T> for RingBuffer<Vec<T>> {
+    /// # Description
+    /// Builds a full ring whose capacity is the number of items in `iter`
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(RingSpecifier::MakeFull(iter.into_iter().collect()))
+    }
+}
+
+impl<T, M> Extend<T> for RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+{
+    /// # Description
+    /// Enqueues items until the ring is full
+    /// # Comments
+    /// This never panics. Items that don't fit are dropped along with the iterator,
+    /// use `enqueue_iter(..)` if you need to know how many were taken
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.enqueue_iter(iter);
+    }
+}
+
+impl io::Write for RingBuffer<Vec<u8>> {
+    /// # Description
+    /// Copies as much of `buf` as fits into the free space behind the rear
+    /// # Comments
+    /// This never blocks, a full ring just reports `Ok(0)` (so `write_all` fails with `WriteZero`)
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(self.enqueue_slice(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Read for RingBuffer<Vec<u8>> {
+    /// # Description
+    /// Dequeues up to `buf.len()` bytes from the front
+    /// # Returns
+    /// `Ok(0)` once the ring is empty
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.dequeue_into(buf))
+    }
+}
+
+impl<T, M> RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+    T: Default,
+{
+    /// # Description
+    /// Dequeues the front item and moves it out of the ring
+    /// # Comments
+    /// The vacated slot is left holding `T::default()` so no stale values are kept alive
+    /// # Returns
+    /// `None` if dequeue fails
+    pub fn dequeue_value(&mut self) -> Option<T> {
+        let idx = self.dequeue()?;
+        Some(std::mem::take(&mut self.slots_mut()[idx]))
+    }
+
+    /// # Description
+    /// Reserves the next slot for in-place construction, nothing is enqueued until the guard is
+    /// committed
+    /// # Comments
+    /// The guard derefs to the reserved slot, which starts out as `T::default()`. `commit()`
+    /// finalizes the enqueue, dropping the guard instead leaves the ring exactly as it was
+    /// (pointers, `len`, stats and the slot's old value, which is put back)
+    /// # Returns
+    /// `None` if the ring is full
+    pub fn enqueue_guard(&mut self) -> Option<EnqueueGuard<'_, M>> {
+        if self.is_full() {
+            None
+        } else {
+            let idx = self.pointers[REAR];
+            let original = Some(std::mem::take(&mut self.slots_mut()[idx]));
+            Some(EnqueueGuard {
+                ring: self,
+                idx,
+                original,
+            })
+        }
+    }
+
+    /// # Description
+    /// Removes and returns the item at logical index `i` (measured from the front)
+    /// # Comments
+    /// The gap is closed by shifting whichever side of `i` is shorter, so at most `len / 2`
+    /// items move. The remaining items keep their order
+    /// # Returns
+    /// `None` if `i >= len`
+    pub fn remove(&mut self, i: usize) -> Option<T> {
+        if i >= self.len {
+            return None;
+        }
+        let front = self.pointers[FRONT];
+        let capacity = self.capacity;
+        let physical = |logical: usize| (front + logical) % capacity;
+        if i < self.len / 2 {
+            // bubble the item to the front, the items before it shift back by one
+            for logical in (0..i).rev() {
+                self.slots_mut()
+                    .swap(physical(logical), physical(logical + 1));
+            }
+            self.dequeue_value()
+        } else {
+            // bubble the item to the rear, the items after it shift forward by one
+            for logical in i..self.len - 1 {
+                self.slots_mut()
+                    .swap(physical(logical), physical(logical + 1));
+            }
+            let idx = self.pop_rear()?;
+            Some(std::mem::take(&mut self.slots_mut()[idx]))
+        }
+    }
+
+    /// # Description
+    /// Cuts the queue at logical index `at`, items `[0, at)` stay in `self` and items `[at, len)`
+    /// are moved (in order) into a new full ring sized to fit them exactly
+    /// # Comments
+    /// Items are moved out with `mem::take`, nothing gets cloned
+    /// # Panics
+    /// if `at > len`
+    pub fn split_off(&mut self, at: usize) -> RingBuffer<Vec<T>> {
+        assert!(
+            at <= self.len,
+            "`at` split index (is {}) should be <= len (is {})",
+            at,
+            self.len
+        );
+        let moved = self.len - at;
+        if moved == 0 {
+            return RingBuffer::new();
+        }
+        let front = self.pointers[FRONT];
+        let capacity = self.capacity;
+        let tail: Vec<T> = (at..self.len)
+            .map(|logical| std::mem::take(&mut self.slots_mut()[(front + logical) % capacity]))
+            .collect();
+        self.len = at;
+        self.pointers[REAR] = (front + at) % capacity;
+        self.record(0, moved);
+        RingBuffer::from(RingSpecifier::MakeFull(tail))
+    }
+
+    /// # Description
+    /// Same as `clear()` but the queued items are actually dropped
+    /// # Comments
+    /// `clear()` only resets the pointers, so the old items stay alive in memory until something
+    /// overwrites them. This resets just the `len` occupied slots to `T::default()`, the rest of
+    /// memory is left alone
+    pub fn clear_drop(&mut self) {
+        for i in self.index_iter() {
+            self.slots_mut()[i] = T::default();
+        }
+        self.clear();
+    }
+
+    /// # Description
+    /// Moves every item out of the ring (front to rear), keeping the capacity
+    /// # Comments
+    /// Like `Vec::drain`, dropping the iterator early still empties the ring
+    pub fn drain(&mut self) -> RingDrain<'_, M> {
+        let remaining = self.len;
+        RingDrain {
+            ring: self,
+            remaining,
+        }
+    }
+
+    /// # Description
+    /// Same as `drain()` but only moves out the first `n` items (or fewer if the ring is shorter)
+    pub fn drain_front(&mut self, n: usize) -> RingDrain<'_, M> {
+        let remaining = n.min(self.len);
+        RingDrain {
+            ring: self,
+            remaining,
+        }
+    }
+
+    /// # Description
+    /// Keeps only the items that satisfy `f`, the survivors keep their relative order
+    /// # Comments
+    /// Survivors get swapped toward the front, so this works in place even when the queue wraps.
+    /// The slots that end up past the new rear are reset to `T::default()`
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len;
+        let mut kept = 0;
+        for logical in 0..len {
+            let src = (self.pointers[FRONT] + logical) % self.capacity;
+            if f(&self.slots()[src]) {
+                let dst = (self.pointers[FRONT] + kept) % self.capacity;
+                self.slots_mut().swap(dst, src);
+                kept += 1;
+            }
+        }
+        for logical in kept..len {
+            let idx = (self.pointers[FRONT] + logical) % self.capacity;
+            self.slots_mut()[idx] = T::default();
+        }
+        if kept < len {
+            self.len = kept;
+            self.pointers[REAR] = (self.pointers[FRONT] + kept) % self.capacity;
+            self.record(0, len - kept);
+        }
+    }
+}
+
+impl<T> RingBuffer<Vec<T>>
+where
+    T: Default,
+{
+    /// # Description
+    /// Makes sure at least `additional` more items can be enqueued
+    /// # Comments
+    /// Calling this may result in an allocation
+    pub fn reserve(&mut self, additional: usize) {
+        let free = self.capacity - self.len;
+        if additional > free {
+            self.resize_capacity(self.capacity + (additional - free));
+        }
+    }
+
+    /// # Description
+    /// Grows the capacity of the ring to `new_cap`, keeping the queued items in order
+    /// # Comments
+    /// - Calling this may result in an allocation
+    /// - This never shrinks the ring, `new_cap <= capacity` does nothing
+    pub fn resize_capacity(&mut self, new_cap: usize) {
+        let old_cap = self.capacity;
+        if new_cap <= old_cap {
+            return;
+        }
+        self.memory.resize_with(new_cap, T::default);
+
+        // if the live region wraps around the end of memory, the new slots would land in the
+        // middle of the queue. Rotating the front run to the end of memory closes that gap
+        let front = self.pointers[FRONT];
+        if self.len > 0 && front + self.len > old_cap {
+            self.memory[front..].rotate_right(new_cap - old_cap);
+            self.pointers[FRONT] = front + (new_cap - old_cap);
+        }
+
+        self.capacity = new_cap;
+        self.pointers[REAR] = (self.pointers[FRONT] + self.len) % new_cap;
+    }
+}
+
+impl<T> RingBuffer<Vec<T>> {
+    /// # Description
+    /// Splits the ring into a lock-free single-producer/single-consumer pair
+    /// # Comments
+    /// - The producer only ever moves the rear pointer and the consumer only ever moves the front
+    /// - Items already queued in the ring can be popped by the consumer
+    pub fn split(self) -> (Producer<T>, Consumer<T>) {
+        let Self {
+            len,
+            capacity,
+            pointers,
+            memory,
+            ..
+        } = self;
+        let front = pointers[FRONT];
+        let slots = memory
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let is_live = (i + capacity - front) % capacity < len;
+                UnsafeCell::new(if is_live { Some(item) } else { None })
+            })
+            .collect();
+        let shared = Arc::new(SpscShared {
+            slots,
+            capacity,
+            front: AtomicUsize::new(front),
+            rear: AtomicUsize::new(front + len),
+        });
+        (
+            Producer {
+                shared: shared.clone(),
+            },
+            Consumer { shared },
+        )
+    }
+}
+/// # Description
+/// Use this enum create and initalize ring buffers to various sizes
+pub enum RingSpecifier<Memory> {
+    /// # Description
+    /// used to create and empty queue with the `Memory`'s length being the capacity
+    /// # Comments
+    /// basically it enqueues nothing in the vector and the capacity of the ring
+    /// comes from the vector's current length
+    MakeEmpty(Memory),
+
+    /// # Description
+    /// used to create and empty queue with the `Memory`'s length being the capacity.
+    /// # Comments
+    /// basically it enqueues everything in the `Memory` and the capacity of the ring
+    /// comes from the `Memory`'s current length
+    MakeFull(Memory),
+}
+
+impl<M> From<RingSpecifier<M>> for RingBuffer<M>
+where
+    M: RingMemory,
+{
+    fn from(spec: RingSpecifier<M>) -> Self {
+        match spec {
+            RingSpecifier::MakeEmpty(mem) => Self {
+                len: 0,
+                pointers: [0, 0],
+                capacity: mem.as_slice().len(),
+                memory: mem,
+                stats: RingStats::starting_at(0),
+                head_seq: 0,
+            },
+            RingSpecifier::MakeFull(mem) => Self {
+                len: mem.as_slice().len(),
+                pointers: [0, 0],
+                capacity: mem.as_slice().len(),
+                stats: RingStats::starting_at(mem.as_slice().len()),
+                head_seq: 0,
+                memory: mem,
+            },
+        }
+    }
+}
+
+impl<M> Clone for RingBuffer<M>
+where
+    M: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            len: self.len,
+            capacity: self.capacity,
+            pointers: self.pointers,
+            memory: self.memory.clone(),
+            stats: self.stats,
+            head_seq: self.head_seq,
+        }
+    }
+}
+
+impl<T, M> fmt::Debug for RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+    T: fmt::Debug,
+{
+    /// # Description
+    /// Prints the queued items front to rear (not the raw memory layout)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, M> PartialEq for RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+    T: PartialEq,
+{
+    /// # Description
+    /// Two rings are equal if they queue the same items in the same order,
+    /// regardless of where those items sit in memory
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, M> Eq for RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+    T: Eq,
+{
+}
+
+/// # Description
+/// `serde` support, enabled with the `serde` feature
+/// # Comments
+/// A ring is written as its capacity plus the queued items (front to rear), pointers never
+/// hit the wire. Deserializing re-enqueues the items starting from index 0
+#[cfg(feature = "serde")]
+mod ring_serde {
+    use super::*;
+    use serde::{
+        de::{self, MapAccess, SeqAccess, Visitor},
+        ser::{SerializeSeq, SerializeStruct},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::marker::PhantomData;
+
+    const FIELDS: &[&str] = &["capacity", "items"];
+
+    enum Field {
+        Capacity,
+        Items,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct FieldVisitor;
+
+            impl<'de> Visitor<'de> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "`capacity` or `items`")
+                }
+
+                fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+                    match value {
+                        "capacity" => Ok(Field::Capacity),
+                        "items" => Ok(Field::Items),
+                        _ => Err(E::unknown_field(value, FIELDS)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct Items<'a, M>(&'a RingBuffer<M>);
+
+    impl<'a, T, M> Serialize for Items<'a, M>
+    where
+        M: RingMemory<Item = T>,
+        T: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+            for item in self.0.iter() {
+                seq.serialize_element(item)?;
+            }
+            seq.end()
+        }
+    }
+
+    impl<T, M> Serialize for RingBuffer<M>
+    where
+        M: RingMemory<Item = T>,
+        T: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("RingBuffer", 2)?;
+            state.serialize_field("capacity", &self.capacity)?;
+            state.serialize_field("items", &Items(self))?;
+            state.end()
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for RingBuffer<Vec<T>>
+    where
+        T: Deserialize<'de> + Default,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_struct("RingBuffer", FIELDS, RingVisitor(PhantomData))
+        }
+    }
+
+    struct RingVisitor<T>(PhantomData<T>);
+
+    impl<T> RingVisitor<T>
+    where
+        T: Default,
+    {
+        fn build<E: de::Error>(
+            capacity: usize,
+            mut items: Vec<T>,
+        ) -> Result<RingBuffer<Vec<T>>, E> {
+            if items.len() > capacity {
+                return Err(E::invalid_length(items.len(), &"at most `capacity` items"));
+            }
+            let len = items.len();
+            // `capacity` comes straight from the input, don't let it abort the process
+            items.try_reserve(capacity - len).map_err(|_| {
+                E::invalid_value(
+                    de::Unexpected::Unsigned(capacity as u64),
+                    &"a capacity that can be allocated",
+                )
+            })?;
+            items.resize_with(capacity, T::default);
+            let mut rb = RingBuffer::from(RingSpecifier::MakeFull(items));
+            rb.len = len;
+            rb.pointers[REAR] = len % capacity.max(1);
+            rb.reset_stats();
+            Ok(rb)
+        }
+    }
+
+    impl<'de, T> Visitor<'de> for RingVisitor<T>
+    where
+        T: Deserialize<'de> + Default,
+    {
+        type Value = RingBuffer<Vec<T>>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a ring buffer with `capacity` and `items` fields")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let capacity = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let items = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            Self::build(capacity, items)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut capacity = None;
+            let mut items = None;
+            while let Some(field) = map.next_key()? {
+                match field {
+                    Field::Capacity => capacity = Some(map.next_value()?),
+                    Field::Items => items = Some(map.next_value()?),
+                }
+            }
+            let capacity = capacity.ok_or_else(|| de::Error::missing_field("capacity"))?;
+            let items = items.ok_or_else(|| de::Error::missing_field("items"))?;
+            Self::build(capacity, items)
+        }
+    }
+}
+
+impl<T, M> ops::Index<Option<usize>> for RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+{
+    type Output = T;
+    /// # Comments
+    /// Panics if `index` is `None`, use `try_get(..)` when the enqueue/dequeue can fail
+    #[track_caller]
+    fn index(&self, index: Option<usize>) -> &Self::Output {
+        match index {
+            Some(i) if i < self.capacity => &self.slots()[i],
+            Some(i) => out_of_bounds(i, self.capacity),
+            None => missing_index(),
+        }
+    }
+}
+impl<T, M> ops::IndexMut<Option<usize>> for RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+{
+    #[track_caller]
+    fn index_mut(&mut self, index: Option<usize>) -> &mut Self::Output {
+        match index {
+            Some(i) if i < self.capacity => &mut self.slots_mut()[i],
+            Some(i) => out_of_bounds(i, self.capacity),
+            None => missing_index(),
+        }
+    }
+}
+
+#[cold]
+#[track_caller]
+fn missing_index() -> ! {
+    panic!(
+        "RingBuffer indexed with `None`: the enqueue/dequeue that produced the index failed \
+         because the ring was full/empty (use `try_get` to handle this)"
+    )
+}
+
+#[cold]
+#[track_caller]
+fn out_of_bounds(index: usize, capacity: usize) -> ! {
+    panic!(
+        "RingBuffer index out of bounds: the capacity is {} but the index is {}",
+        capacity, index
+    )
+}
+
+impl<T, M> ops::Index<usize> for RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+{
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.slots()[index]
+    }
+}
+
+impl<T, M> ops::IndexMut<usize> for RingBuffer<M>
+where
+    M: RingMemory<Item = T>,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.slots_mut()[index]
+    }
+}
+
+pub struct RingIter {
+    cur: usize,
+    cap: usize,
+    len: usize,
+}
+
+impl Iterator for RingIter {
+    type Item = usize;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            let old_cur = self.cur;
+            self.len -= 1;
+            self.cur = (self.cur + 1) % self.cap;
+            Some(old_cur)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl DoubleEndedIterator for RingIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            Some((self.cur + self.len) % self.cap)
+        }
+    }
+}
+
+impl ExactSizeIterator for RingIter {}
+
+/// # Description
+/// Yields the queued items mutably, see `RingBuffer::iter_mut()`
+/// # Comments
+/// `Chain` would do the job but it doesn't implement `ExactSizeIterator`
+struct RingIterMut<'a, T> {
+    first: std::slice::IterMut<'a, T>,
+    second: std::slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for RingIterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.first.next().or_else(|| self.second.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.first.len() + self.second.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for RingIterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.second.next_back().or_else(|| self.first.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for RingIterMut<'a, T> {}
+
+/// # Description
+/// Moves items out of a ring, see `RingBuffer::drain(..)`
+pub struct RingDrain<'a, M>
+where
+    M: RingMemory,
+    M::Item: Default,
+{
+    ring: &'a mut RingBuffer<M>,
+    remaining: usize,
+}
+
+impl<'a, M> Iterator for RingDrain<'a, M>
+where
+    M: RingMemory,
+    M::Item: Default,
+{
+    type Item = M::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            None
+        } else {
+            self.remaining -= 1;
+            self.ring.dequeue_value()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, M> ExactSizeIterator for RingDrain<'a, M>
+where
+    M: RingMemory,
+    M::Item: Default,
+{
+}
+
+impl<'a, M> Drop for RingDrain<'a, M>
+where
+    M: RingMemory,
+    M::Item: Default,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+        if self.ring.is_empty() {
+            self.ring.clear();
+        }
+    }
+}
+
+/// # Description
+/// returns the logical run `start..start + n` of a ring given its `as_slices()` pair
+/// # Comments
+/// The run is split in two when it straddles the wrap point, otherwise the second slice is empty
+fn logical_run<'a, T>(
+    (first, second): (&'a [T], &'a [T]),
+    start: usize,
+    n: usize,
+) -> (&'a [T], &'a [T]) {
+    let end = start + n;
+    if start >= first.len() {
+        (&second[start - first.len()..end - first.len()], &[])
+    } else if end <= first.len() {
+        (&first[start..end], &[])
+    } else {
+        (&first[start..], &second[..end - first.len()])
+    }
+}
+
+/// # Description
+/// Overlapping windows over a ring, see `RingBuffer::windows(..)`
+pub struct RingWindows<'a, T> {
+    slices: (&'a [T], &'a [T]),
+    start: usize,
+    size: usize,
+}
+
+impl<'a, T> Iterator for RingWindows<'a, T> {
+    type Item = (&'a [T], &'a [T]);
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.slices.0.len() + self.slices.1.len();
+        if self.start + self.size > len {
+            None
+        } else {
+            let window = logical_run(self.slices, self.start, self.size);
+            self.start += 1;
+            Some(window)
+        }
+    }
+}
+
+/// # Description
+/// An independent read position into a ring, see `RingBuffer::cursor()`
+/// # Comments
+/// A cursor is just a sequence number, it's only meaningful for the ring that created it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cursor {
+    next: u64,
+}
+
+/// # Description
+/// Returned by `RingBuffer::read_cursor(..)` when items were dropped before the cursor read them
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Overrun {
+    /// how many items the cursor skipped over
+    pub missed: u64,
+}
+
+/// # Description
+/// A slot reserved by `RingBuffer::enqueue_guard()`
+pub struct EnqueueGuard<'a, M>
+where
+    M: RingMemory,
+{
+    ring: &'a mut RingBuffer<M>,
+    idx: usize,
+    /// what the slot held before it was reserved, `None` once committed
+    original: Option<M::Item>,
+}
+
+impl<'a, M> EnqueueGuard<'a, M>
+where
+    M: RingMemory,
+{
+    /// # Description
+    /// Finalizes the enqueue
+    /// # Returns
+    /// index of the slot that was enqueued
+    pub fn commit(mut self) -> usize {
+        // the guard holds the only borrow of a ring that had room, so this can't fail
+        self.ring.enqueue();
+        self.original = None;
+        self.idx
+    }
+}
+
+impl<'a, M> Drop for EnqueueGuard<'a, M>
+where
+    M: RingMemory,
+{
+    fn drop(&mut self) {
+        if let Some(original) = self.original.take() {
+            let idx = self.idx;
+            self.ring.slots_mut()[idx] = original;
+        }
+    }
+}
+
+impl<'a, T, M> ops::Deref for EnqueueGuard<'a, M>
+where
+    M: RingMemory<Item = T>,
+{
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.ring.slots()[self.idx]
+    }
+}
+
+impl<'a, T, M> ops::DerefMut for EnqueueGuard<'a, M>
+where
+    M: RingMemory<Item = T>,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        let idx = self.idx;
+        &mut self.ring.slots_mut()[idx]
+    }
+}
+
+/// # Description
+/// Non-overlapping chunks over a ring, see `RingBuffer::chunks(..)`
+pub struct RingChunks<'a, T> {
+    slices: (&'a [T], &'a [T]),
+    start: usize,
+    size: usize,
+}
+
+impl<'a, T> Iterator for RingChunks<'a, T> {
+    type Item = (&'a [T], &'a [T]);
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.slices.0.len() + self.slices.1.len();
+        if self.start >= len {
+            None
+        } else {
+            let n = self.size.min(len - self.start);
+            let chunk = logical_run(self.slices, self.start, n);
+            self.start += n;
+            Some(chunk)
+        }
+    }
+}
+
+/// # Description
+/// Memory shared by a `Producer`/`Consumer` pair
+/// # Comments
+/// `front` and `rear` run over `[0, 2*capacity)` instead of `[0, capacity)` so that
+/// a full ring and an empty ring can be told apart without a shared `len`
+struct SpscShared<T> {
+    slots: Box<[UnsafeCell<Option<T>>]>,
+    capacity: usize,
+    front: AtomicUsize,
+    rear: AtomicUsize,
+}
+
+/// The producer owns the slots between `rear` and `front`, the consumer owns the rest,
+/// so a slot is never touched by both threads at the same time
+unsafe impl<T: Send> Sync for SpscShared<T> {}
+
+impl<T> SpscShared<T> {
+    fn len(&self) -> usize {
+        let front = self.front.load(Ordering::Acquire);
+        let rear = self.rear.load(Ordering::Acquire);
+        self.distance(front, rear)
+    }
+
+    fn distance(&self, front: usize, rear: usize) -> usize {
+        (rear + 2 * self.capacity - front) % (2 * self.capacity).max(1)
+    }
+
+    fn step(&self, ptr: usize) -> usize {
+        (ptr + 1) % (2 * self.capacity)
+    }
+}
+
+/// # Description
+/// The writing half of a split `RingBuffer`, see `RingBuffer::split(..)`
+pub struct Producer<T> {
+    shared: Arc<SpscShared<T>>,
+}
+
+impl<T> Producer<T> {
+    /// # Description
+    /// Pushes `value` onto the rear of the ring
+    /// # Returns
+    /// `Err(value)` if the ring is full
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        let shared = &*self.shared;
+        let rear = shared.rear.load(Ordering::Relaxed);
+        let front = shared.front.load(Ordering::Acquire);
+        if shared.distance(front, rear) >= shared.capacity {
+            return Err(value);
+        }
+        // the consumer won't look at this slot until `rear` is published below
+        unsafe {
+            *shared.slots[rear % shared.capacity].get() = Some(value);
+        }
+        shared.rear.store(shared.step(rear), Ordering::Release);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+/// # Description
+/// The reading half of a split `RingBuffer`, see `RingBuffer::split(..)`
+pub struct Consumer<T> {
+    shared: Arc<SpscShared<T>>,
+}
+
+impl<T> Consumer<T> {
+    /// # Description
+    /// Pops an item off the front of the ring
+    /// # Returns
+    /// `None` if the ring is empty
+    pub fn pop(&mut self) -> Option<T> {
+        let shared = &*self.shared;
+        let front = shared.front.load(Ordering::Relaxed);
+        let rear = shared.rear.load(Ordering::Acquire);
+        if shared.distance(front, rear) == 0 {
+            return None;
+        }
+        // the producer won't write to this slot until `front` is published below
+        let item = unsafe { (*shared.slots[front % shared.capacity].get()).take() };
+        shared.front.store(shared.step(front), Ordering::Release);
+        item
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+/// # Description
+/// A bounded blocking queue: `push` waits while the ring is full and `pop` waits while it's empty
+/// # Comments
+/// - A `RingBuffer` behind a `Mutex`, with one `Condvar` per direction. Share it with an `Arc`
+/// - Items are stored as `Option<T>` so `T` doesn't need `Default`
+/// - Every push wakes one waiting consumer and every pop wakes one waiting producer
+pub struct BlockingRing<T> {
+    ring: Mutex<RingBuffer<Vec<Option<T>>>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<T> BlockingRing<T> {
+    /// # Panics
+    /// if `capacity` is 0, `push` would block forever
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "a BlockingRing needs a non-zero capacity");
+        Self {
+            ring: Mutex::new(RingBuffer::new().with_capacity(capacity)),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RingBuffer<Vec<Option<T>>>> {
+        self.ring.lock().unwrap()
+    }
+
+    /// # Description
+    /// enqueues `value` under the lock and wakes a consumer, the caller made sure there's room
+    fn push_locked(&self, mut ring: MutexGuard<'_, RingBuffer<Vec<Option<T>>>>, value: T) {
+        ring.enqueue_value(Some(value));
+        drop(ring);
+        self.not_empty.notify_one();
+    }
+
+    /// # Description
+    /// dequeues the front item under the lock and wakes a producer, the caller made sure
+    /// there's something to dequeue
+    fn pop_locked(&self, mut ring: MutexGuard<'_, RingBuffer<Vec<Option<T>>>>) -> Option<T> {
+        let value = ring.dequeue_value().flatten();
+        drop(ring);
+        self.not_full.notify_one();
+        value
+    }
+
+    /// # Description
+    /// Enqueues `value`, blocking while the ring is full
+    pub fn push(&self, value: T) {
+        let ring = self
+            .not_full
+            .wait_while(self.lock(), |rb| rb.is_full())
+            .unwrap();
+        self.push_locked(ring, value);
+    }
+
+    /// # Description
+    /// Dequeues the front item, blocking while the ring is empty
+    pub fn pop(&self) -> T {
+        let ring = self
+            .not_empty
+            .wait_while(self.lock(), |rb| rb.is_empty())
+            .unwrap();
+        self.pop_locked(ring)
+            .expect("a queued BlockingRing slot is always Some")
+    }
+
+    /// # Returns
+    /// `Err(value)` right away if the ring is full
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        let ring = self.lock();
+        if ring.is_full() {
+            return Err(value);
+        }
+        self.push_locked(ring, value);
+        Ok(())
+    }
+
+    /// # Returns
+    /// `None` right away if the ring is empty
+    pub fn try_pop(&self) -> Option<T> {
+        let ring = self.lock();
+        if ring.is_empty() {
+            return None;
+        }
+        self.pop_locked(ring)
+    }
+
+    /// # Description
+    /// Same as `push(..)` but gives up after `timeout`
+    /// # Returns
+    /// `Err(value)` if the ring was still full when the timeout ran out
+    pub fn push_timeout(&self, value: T, timeout: Duration) -> Result<(), T> {
+        let (ring, _) = self
+            .not_full
+            .wait_timeout_while(self.lock(), timeout, |rb| rb.is_full())
+            .unwrap();
+        if ring.is_full() {
+            return Err(value);
+        }
+        self.push_locked(ring, value);
+        Ok(())
+    }
+
+    /// # Description
+    /// Same as `pop()` but gives up after `timeout`
+    /// # Returns
+    /// `None` if the ring was still empty when the timeout ran out
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        let (ring, _) = self
+            .not_empty
+            .wait_timeout_while(self.lock(), timeout, |rb| rb.is_empty())
+            .unwrap();
+        if ring.is_empty() {
+            return None;
+        }
+        self.pop_locked(ring)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+}
+
+/// # Description
+/// Rolling min/max over the last `window` pushed items, in amortized O(1) per push
+/// # Comments
+/// The samples live in one ring and two more rings act as monotonic deques of sequence numbers:
+/// candidates for the minimum (increasing values front to rear) and for the maximum (decreasing).
+/// Each sequence number enters and leaves a deque at most once, hence the amortized O(1)
+pub struct MonotonicRing<T> {
+    window: RingBuffer<Vec<Option<T>>>,
+    min_seqs: RingBuffer<Vec<u64>>,
+    max_seqs: RingBuffer<Vec<u64>>,
+    /// sequence number of the next pushed item
+    next_seq: u64,
+}
+
+impl<T> MonotonicRing<T>
+where
+    T: Ord,
+{
+    /// # Panics
+    /// if `window` is 0
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "a MonotonicRing needs a non-zero window");
+        Self {
+            window: RingBuffer::new().with_capacity(window),
+            min_seqs: RingBuffer::new().with_capacity(window),
+            max_seqs: RingBuffer::new().with_capacity(window),
+            next_seq: 0,
+        }
+    }
+
+    /// # Description
+    /// returns the sample with sequence number `seq`, which has to still be inside the window
+    fn sample(&self, seq: u64) -> &T {
+        let oldest = self.next_seq - self.window.len() as u64;
+        self.window
+            .get((seq - oldest) as usize)
+            .and_then(Option::as_ref)
+            .expect("sequence number outside of the window")
+    }
+
+    /// # Description
+    /// Pushes `value` into the window, evicting the oldest sample once the window is full
+    pub fn push(&mut self, value: T) {
+        let seq = self.next_seq;
+        let window = self.window.capacity as u64;
+
+        // drop candidates that are about to slide out of the window
+        if seq >= window {
+            let leaving = seq - window;
+            for deque in [&mut self.min_seqs, &mut self.max_seqs].iter_mut() {
+                if deque.peek_front() == Some(&leaving) {
+                    deque.dequeue();
+                }
+            }
+        }
+
+        // candidates that can never win again once `value` is in the window
+        while let Some(&rear) = self.min_seqs.peek_rear() {
+            if *self.sample(rear) > value {
+                self.min_seqs.pop_rear();
+            } else {
+                break;
+            }
+        }
+        while let Some(&rear) = self.max_seqs.peek_rear() {
+            if *self.sample(rear) < value {
+                self.max_seqs.pop_rear();
+            } else {
+                break;
+            }
+        }
+
+        self.window.push_overwrite(Some(value));
+        self.min_seqs.enqueue_value(seq);
+        self.max_seqs.enqueue_value(seq);
+        self.next_seq += 1;
+    }
+
+    /// # Returns
+    /// the smallest sample in the window, `None` if nothing was pushed yet
+    pub fn min(&self) -> Option<&T> {
+        self.min_seqs.peek_front().map(|&seq| self.sample(seq))
+    }
+
+    /// # Returns
+    /// the largest sample in the window, `None` if nothing was pushed yet
+    pub fn max(&self) -> Option<&T> {
+        self.max_seqs.peek_front().map(|&seq| self.sample(seq))
+    }
+
+    /// # Description
+    /// returns the number of samples currently in the window
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn ring_buffer_base_cases() {
+    let rb: RingBuffer<Vec<i32>> = RingBuffer::new();
+
+    assert_eq!(rb.to_vec(), vec![]);
+    assert_eq!(rb.is_empty(), true);
+    assert_eq!(rb.is_full(), true);
+
+    let rb = RingBuffer::from(RingSpecifier::MakeFull(vec![0]));
+    assert_eq!(rb.to_vec(), vec![0]);
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), true);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn ring_buffer_deq_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeFull(vec![1, 2, 3, 4, 5, 6, 7]));
+
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), true);
+    assert_eq!(rb.to_vec(), vec![1, 2, 3, 4, 5, 6, 7]);
+
+    let decd_item = rb.dequeue();
+    assert_eq!(rb[decd_item], 1);
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), false);
+    assert_eq!(rb.to_vec(), vec![2, 3, 4, 5, 6, 7]);
 
     let decd_item = rb.dequeue();
     assert_eq!(rb[decd_item], 2);
     assert_eq!(rb.is_empty(), false);
     assert_eq!(rb.is_full(), false);
+    assert_eq!(rb.to_vec(), vec![3, 4, 5, 6, 7]);
+
+    let popped_item = rb.pop_rear();
+    assert_eq!(rb[popped_item], 7);
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), false);
+    assert_eq!(rb.to_vec(), vec![3, 4, 5, 6]);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn ring_buffer_enq_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![1, 2, 3, 4]));
+
+    assert_eq!(rb.is_empty(), true);
+    assert_eq!(rb.is_full(), false);
+    assert_eq!(rb.to_vec(), vec![]);
+
+    let idx = rb.enqueue();
+    rb[idx] = -1;
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), false);
+    assert_eq!(rb.to_vec(), vec![-1]);
+
+    let idx = rb.enqueue();
+    rb[idx] = -2;
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), false);
+    assert_eq!(rb.to_vec(), vec![-1, -2]);
+
+    let idx = rb.enqueue();
+    rb[idx] = -3;
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), false);
+    assert_eq!(rb.to_vec(), vec![-1, -2, -3]);
+
+    let idx = rb.enqueue();
+    rb[idx] = -4;
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), true);
+    assert_eq!(rb.to_vec(), vec![-1, -2, -3, -4]);
+
+    let idx = rb.dequeue();
+    assert_eq!(rb[idx], -1);
+    assert_eq!(rb.is_empty(), false);
+    assert_eq!(rb.is_full(), false);
+    assert_eq!(rb.len(), 3);
+    assert_eq!(rb.to_vec(), vec![-2, -3, -4]);
+
+    let front = rb.front();
+    let next_idx = rb.peek_next();
+    assert_eq!(rb[front], -2);
+    assert_eq!(rb[next_idx], -3);
+}
+
+#[test]
+fn ring_buffer_value_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 3]));
+
+    assert_eq!(rb.dequeue_value(), None);
+
+    assert_eq!(rb.enqueue_value(String::from("a")), Some(0));
+    assert_eq!(rb.enqueue_value(String::from("b")), Some(1));
+    assert_eq!(rb.enqueue_value(String::from("c")), Some(2));
+    assert!(rb.is_full());
+    assert_eq!(rb.enqueue_value(String::from("d")), None);
+    assert_eq!(rb.to_vec(), ["a", "b", "c"]);
+
+    assert_eq!(rb.dequeue_value(), Some(String::from("a")));
+    assert_eq!(rb[0], "");
+
+    // rear wraps around to index 0
+    assert_eq!(rb.enqueue_value(String::from("d")), Some(0));
+    assert_eq!(rb.to_vec(), ["b", "c", "d"]);
+
+    assert_eq!(rb.dequeue_value(), Some(String::from("b")));
+    assert_eq!(rb.dequeue_value(), Some(String::from("c")));
+    assert_eq!(rb.dequeue_value(), Some(String::from("d")));
+    assert_eq!(rb.dequeue_value(), None);
+    assert!(rb.is_empty());
+    assert!(rb.iter().all(|s| s.is_empty()));
+    assert_eq!(rb.memory, ["", "", ""]);
+}
+
+#[test]
+fn ring_buffer_peek_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 3]));
+    assert_eq!(rb.peek_front(), None);
+    assert_eq!(rb.peek_rear(), None);
+    assert_eq!(rb.peek_rear_mut(), None);
+
+    rb.enqueue_value(1);
+    assert_eq!(rb.peek_front(), Some(&1));
+    assert_eq!(rb.peek_rear(), Some(&1));
+
+    rb.enqueue_value(2);
+    rb.enqueue_value(3);
+    assert_eq!(rb.peek_front(), Some(&1));
+    assert_eq!(rb.peek_rear(), Some(&3));
+
+    // rear pointer wraps to 0 here, so the last item sits at the end of memory
+    rb.dequeue();
+    rb.enqueue_value(4);
+    assert_eq!(rb.rear(), 1);
+    assert_eq!(rb.peek_front(), Some(&2));
+    assert_eq!(rb.peek_rear(), Some(&4));
+
+    *rb.peek_front_mut().unwrap() = 20;
+    *rb.peek_rear_mut().unwrap() = 40;
+    assert_eq!(rb.to_vec(), vec![20, 3, 40]);
+
+    rb.clear();
+    assert_eq!(rb.peek_front_mut(), None);
+    assert_eq!(rb.peek_rear(), None);
+}
+
+#[test]
+fn ring_buffer_from_iter_extend_tests() {
+    let rb: RingBuffer<Vec<i32>> = (1..=4).collect();
+    assert!(rb.is_full());
+    assert_eq!(rb.to_vec(), vec![1, 2, 3, 4]);
+
+    let rb: RingBuffer<Vec<i32>> = std::iter::empty().collect();
+    assert!(rb.is_empty());
+    assert!(rb.is_full());
+
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 4]));
+    rb.enqueue_value(1);
+    rb.enqueue_value(2);
+    rb.dequeue();
+
+    // only 3 slots are free, the rest of the iterator must stay untouched
+    let mut source = 10..20;
+    assert_eq!(rb.enqueue_iter(&mut source), 3);
+    assert_eq!(source.next(), Some(13));
+    assert_eq!(rb.to_vec(), vec![2, 10, 11, 12]);
+    assert_eq!(rb.enqueue_iter(source), 0);
+
+    rb.dequeue();
+    rb.dequeue();
+    rb.extend(vec![30, 31, 32]);
+    assert_eq!(rb.to_vec(), vec![11, 12, 30, 31]);
+}
+
+#[test]
+fn ring_buffer_push_overwrite_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 3]));
+    assert_eq!(rb.push_overwrite(1), None);
+    assert_eq!(rb.push_overwrite(2), None);
+    assert_eq!(rb.push_overwrite(3), None);
+    assert!(rb.is_full());
+
+    // go around the ring a few times
+    for k in 4..=10 {
+        assert_eq!(rb.push_overwrite(k), Some(k - 3));
+        assert!(rb.is_full());
+        assert_eq!(rb.to_vec(), vec![k - 2, k - 1, k]);
+    }
+
+    rb.dequeue();
+    assert_eq!(rb.push_overwrite(11), None);
+    assert_eq!(rb.to_vec(), vec![9, 10, 11]);
+
+    let mut rb: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_eq!(rb.push_overwrite(1), Some(1));
+    assert!(rb.is_empty());
+}
+
+#[test]
+fn ring_buffer_grow_tests() {
+    let to_vec = |rb: &RingBuffer<Vec<i32>>| rb.to_vec();
+
+    // wrapped and full
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 4]));
+    rb.extend(1..=4);
+    rb.dequeue();
+    rb.dequeue();
+    rb.extend(5..=6);
+    assert!(rb.is_full());
+    assert_eq!(to_vec(&rb), vec![3, 4, 5, 6]);
+
+    rb.resize_capacity(7);
+    assert_eq!(rb.len(), 4);
+    assert!(!rb.is_full());
+    assert_eq!(to_vec(&rb), vec![3, 4, 5, 6]);
+    assert_eq!(rb.enqueue_iter(7..), 3);
+    assert_eq!(to_vec(&rb), vec![3, 4, 5, 6, 7, 8, 9]);
+
+    // contiguous with the rear pointer sitting at the end of memory
+    let mut rb = RingBuffer::from(RingSpecifier::MakeFull(vec![1, 2, 3]));
+    rb.dequeue();
+    rb.reserve(2);
+    assert_eq!(rb.enqueue_iter(4..), 2);
+    assert_eq!(to_vec(&rb), vec![2, 3, 4, 5]);
+
+    // reserve only grows when there isn't enough free space
+    rb.dequeue();
+    rb.reserve(1);
+    assert!(rb.enqueue_value(6).is_some());
+    assert!(rb.is_full());
+    rb.resize_capacity(2);
+    assert_eq!(to_vec(&rb), vec![3, 4, 5, 6]);
+
+    let mut rb: RingBuffer<Vec<i32>> = RingBuffer::new();
+    rb.reserve(2);
+    rb.extend(vec![1, 2, 3]);
+    assert_eq!(to_vec(&rb), vec![1, 2]);
+}
+
+#[test]
+fn ring_buffer_drain_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 4]));
+    rb.extend(["a", "b", "c", "d"].iter().map(|s| s.to_string()));
+    rb.dequeue_value();
+    rb.enqueue_value(String::from("e"));
+
+    let drained = rb.drain().collect::<Vec<_>>();
+    assert_eq!(drained, ["b", "c", "d", "e"]);
+    assert!(rb.is_empty());
+    assert_eq!((rb.front(), rb.rear()), (0, 0));
+    assert!(rb.memory.iter().all(|s| s.is_empty()));
+
+    // dropping a drain midway still empties the ring
+    rb.extend(["f", "g", "h"].iter().map(|s| s.to_string()));
+    let mut drain = rb.drain();
+    assert_eq!(drain.len(), 3);
+    assert_eq!(drain.next(), Some(String::from("f")));
+    drop(drain);
+    assert!(rb.is_empty());
+    assert!(rb.memory.iter().all(|s| s.is_empty()));
+
+    rb.extend(["i", "j", "k"].iter().map(|s| s.to_string()));
+    assert_eq!(rb.drain_front(2).collect::<Vec<_>>(), ["i", "j"]);
+    assert_eq!(rb.to_vec(), ["k"]);
+    drop(rb.drain_front(10));
+    assert!(rb.is_empty());
+}
+
+#[test]
+fn ring_buffer_slices_tests() {
+    let empty: &[i32] = &[];
+
+    let mut rb: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_eq!(rb.as_slices(), (empty, empty));
+
+    let mut rb2 = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 5]));
+    assert_eq!(rb2.as_slices(), (empty, empty));
+    rb2.extend(1..=3);
+    assert_eq!(rb2.as_slices(), (&[1, 2, 3][..], empty));
+
+    // full with front == rear == 0
+    rb = RingBuffer::from(RingSpecifier::MakeFull(vec![1, 2, 3, 4]));
+    assert_eq!(rb.as_slices(), (&[1, 2, 3, 4][..], empty));
+
+    // full with front == rear != 0
+    rb.dequeue();
+    rb.dequeue();
+    rb.extend(5..=6);
+    assert_eq!(rb.front(), rb.rear());
+    assert_eq!(rb.as_slices(), (&[3, 4][..], &[5, 6][..]));
+
+    let (first, second) = rb.as_mut_slices();
+    first[0] = 30;
+    second[1] = 60;
+    assert_eq!(rb.to_vec(), vec![30, 4, 5, 60]);
+
+    rb.dequeue();
+    rb.dequeue();
+    assert_eq!(rb.as_slices(), (&[5, 60][..], empty));
+}
+
+#[test]
+fn ring_buffer_make_contiguous_tests() {
+    let empty: &[i32] = &[];
+
+    // wrapped
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 5]));
+    rb.extend(1..=5);
+    rb.dequeue();
+    rb.dequeue();
+    rb.dequeue();
+    rb.extend(6..=7);
+    assert_eq!(rb.as_slices(), (&[4, 5][..], &[6, 7][..]));
+    rb.make_contiguous().sort_by(|a, b| b.cmp(a));
+    assert_eq!(rb.as_slices(), (&[7, 6, 5, 4][..], empty));
+    assert_eq!((rb.front(), rb.rear()), (0, 4));
+    rb.extend(8..);
+    assert_eq!(rb.to_vec(), vec![7, 6, 5, 4, 8]);
+
+    // already contiguous, but not at the start of memory
+    rb.dequeue();
+    rb.dequeue();
+    rb.pop_rear();
+    assert_eq!(rb.make_contiguous(), &[5, 4]);
+    assert_eq!((rb.front(), rb.rear()), (0, 2));
+
+    // full
+    rb.extend(9..);
+    rb.dequeue();
+    rb.enqueue_value(12);
+    assert_eq!(rb.make_contiguous(), &[4, 9, 10, 11, 12]);
+    assert_eq!((rb.front(), rb.rear()), (0, 0));
+    assert!(rb.is_full());
+
+    let mut rb: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_eq!(rb.make_contiguous(), empty);
+}
+
+#[test]
+fn ring_buffer_array_tests() {
+    static SHARED: std::sync::Mutex<RingBuffer<[u8; 64]>> =
+        std::sync::Mutex::new(RingBuffer::from_array([0; 64]));
+
+    let mut rb: RingBuffer<[i32; 3]> = RingBuffer::new();
+    assert_eq!(rb.len(), 0);
+    assert!(rb.is_empty());
+    assert!(!rb.is_full());
+
+    assert_eq!(rb.enqueue_value(1), Some(0));
+    let idx = rb.enqueue();
+    rb[idx] = 2;
+    rb.enqueue_value(3);
+    assert_eq!(rb.enqueue_value(4), None);
+    assert!(rb.is_full());
+
+    assert_eq!(rb.dequeue_value(), Some(1));
+    assert_eq!(rb.push_overwrite(4), None);
+    assert_eq!(rb.push_overwrite(5), Some(2));
+    assert_eq!(rb.to_vec(), vec![3, 4, 5]);
+
+    rb.iter_mut().for_each(|x| *x *= 10);
+    assert_eq!(rb.as_slices(), (&[30][..], &[40, 50][..]));
+    assert_eq!(rb.make_contiguous(), &[30, 40, 50]);
+    assert_eq!(rb.drain().collect::<Vec<_>>(), vec![30, 40, 50]);
+    assert!(rb.is_empty());
+
+    let rb = RingBuffer::from(RingSpecifier::MakeFull([1, 2]));
+    assert_eq!(rb.to_vec(), vec![1, 2]);
+
+    let mut shared = SHARED.lock().unwrap();
+    assert_eq!(shared.enqueue_iter(0..100), 64);
+    assert!(shared.is_full());
+}
+
+#[test]
+fn ring_buffer_slice_tests() {
+    let mut arena = [0; 8];
+    let (left, right) = arena.split_at_mut(3);
+
+    let mut a = RingBuffer::from_slice(left);
+    let mut b = RingBuffer::from_slice(right);
+    assert!(a.is_empty());
+    assert_eq!(a.enqueue_iter(1..), 3);
+    assert_eq!(b.enqueue_iter(10..), 5);
+
+    assert_eq!(a.dequeue_value(), Some(1));
+    a.enqueue_value(4);
+    assert_eq!(a.to_vec(), vec![2, 3, 4]);
+    assert_eq!(b.drain_front(2).collect::<Vec<_>>(), vec![10, 11]);
+
+    b.clear();
+    assert!(b.is_empty());
+    assert_eq!(b.enqueue_iter(20..), 5);
+    b.iter_mut().for_each(|x| *x += 1);
+    assert_eq!(b.pop_rear().map(|i| b[i]), Some(25));
+
+    // the rings' borrows end here, handing the memory back
+    assert_eq!(arena, [4, 2, 3, 21, 22, 23, 24, 25]);
+}
+
+#[test]
+fn ring_buffer_spsc_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 3]));
+    rb.extend(1..=3);
+    rb.dequeue();
+    rb.enqueue_value(4);
+
+    let (mut producer, mut consumer) = rb.split();
+    assert_eq!(producer.len(), 3);
+    assert_eq!(producer.push(5), Err(5));
+    assert_eq!(consumer.pop(), Some(2));
+    assert_eq!(producer.push(5), Ok(()));
+    assert_eq!(consumer.pop(), Some(3));
+    assert_eq!(consumer.pop(), Some(4));
+    assert_eq!(consumer.pop(), Some(5));
+    assert_eq!(consumer.pop(), None);
+    assert!(consumer.is_empty());
+
+    let (mut producer, mut consumer) = RingBuffer::<Vec<i32>>::new().split();
+    assert_eq!(producer.push(1), Err(1));
+    assert_eq!(consumer.pop(), None);
+}
+
+#[test]
+fn ring_buffer_spsc_stress_test() {
+    const COUNT: usize = 200_000;
+    let rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 7]));
+    let (mut producer, mut consumer) = rb.split();
+
+    let writer = std::thread::spawn(move || {
+        for k in 0..COUNT {
+            let mut item = k.to_string();
+            while let Err(rejected) = producer.push(item) {
+                item = rejected;
+                std::thread::yield_now();
+            }
+        }
+    });
+
+    let reader = std::thread::spawn(move || {
+        let mut expected = 0;
+        while expected < COUNT {
+            match consumer.pop() {
+                Some(item) => {
+                    assert_eq!(item, expected.to_string());
+                    expected += 1;
+                }
+                None => std::thread::yield_now(),
+            }
+        }
+        assert_eq!(consumer.pop(), None);
+    });
+
+    writer.join().unwrap();
+    reader.join().unwrap();
+}
+
+#[test]
+fn ring_buffer_clone_debug_eq_tests() {
+    let a = RingBuffer::from(RingSpecifier::MakeFull(vec![1, 2, 3, 4]));
+
+    // same contents, different rotation
+    let mut b = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 4]));
+    b.extend(vec![9, 9, 1, 2]);
+    b.dequeue();
+    b.dequeue();
+    b.extend(3..=4);
+    assert_ne!(a.front(), b.front());
+    assert_eq!(a, b);
+    assert_eq!(format!("{:?}", b), "[1, 2, 3, 4]");
+
+    let mut c = b.clone();
+    assert_eq!(c, b);
+    assert_eq!((c.front(), c.rear()), (b.front(), b.rear()));
+    c.pop_rear();
+    assert_ne!(c, b);
+    assert_eq!(format!("{:?}", c), "[1, 2, 3]");
+
+    // a different capacity doesn't matter, only the queued items
+    let mut d = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 10]));
+    d.extend(1..=3);
+    assert_eq!(c, d);
+
+    let empty: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_eq!(format!("{:?}", empty), "[]");
+    assert_ne!(empty, d);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn ring_buffer_serde_tests() {
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    // wrapped
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 4]));
+    rb.extend(1..=4);
+    rb.dequeue();
+    rb.dequeue();
+    rb.dequeue();
+    rb.extend(5..=6);
+    assert_eq!(rb.as_slices(), (&[4][..], &[5, 6][..]));
+
+    assert_tokens(
+        &rb,
+        &[
+            Token::Struct {
+                name: "RingBuffer",
+                len: 2,
+            },
+            Token::Str("capacity"),
+            Token::U64(4),
+            Token::Str("items"),
+            Token::Seq { len: Some(3) },
+            Token::I32(4),
+            Token::I32(5),
+            Token::I32(6),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+
+    let empty: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_tokens(
+        &empty,
+        &[
+            Token::Struct {
+                name: "RingBuffer",
+                len: 2,
+            },
+            Token::Str("capacity"),
+            Token::U64(0),
+            Token::Str("items"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<RingBuffer<Vec<i32>>>(
+        &[
+            Token::Struct {
+                name: "RingBuffer",
+                len: 2,
+            },
+            Token::Str("capacity"),
+            Token::U64(1),
+            Token::Str("items"),
+            Token::Seq { len: Some(2) },
+            Token::I32(1),
+            Token::I32(2),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+        "invalid length 2, expected at most `capacity` items",
+    );
+
+    assert_de_tokens_error::<RingBuffer<Vec<i32>>>(
+        &[
+            Token::Struct {
+                name: "RingBuffer",
+                len: 2,
+            },
+            Token::Str("capacity"),
+            Token::U64(u64::MAX),
+            Token::Str("items"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+        "invalid value: integer `18446744073709551615`, expected a capacity that can be allocated",
+    );
+}
+
+#[test]
+fn ring_buffer_get_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 4]));
+    assert_eq!(rb.get(0), None);
+
+    rb.extend(1..=4);
+    rb.dequeue();
+    rb.dequeue();
+    rb.extend(5..=6);
+
+    // raw indexing sees memory, logical indexing sees the queue
+    assert_eq!(rb[0], 5);
+    assert_eq!(rb.get(0), Some(&3));
+    assert_eq!(
+        (0..4).map(|i| *rb.get(i).unwrap()).collect::<Vec<_>>(),
+        rb.to_vec()
+    );
+    assert_eq!(rb.get(3), Some(&6));
+    assert_eq!(rb.get(4), None);
+
+    *rb.get_mut(2).unwrap() = 50;
+    assert_eq!(rb.to_vec(), vec![3, 4, 50, 6]);
+    assert_eq!(rb.get_mut(4), None);
+
+    rb.pop_rear();
+    assert_eq!(rb.get(3), None);
+}
+
+#[test]
+fn ring_buffer_rev_iter_tests() {
+    // front > rear
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 5]));
+    rb.extend(1..=5);
+    rb.dequeue();
+    rb.dequeue();
+    rb.dequeue();
+    rb.extend(6..=7);
+    assert!(rb.front() > rb.rear());
+
+    let rev: Vec<_> = rb.iter().rev().copied().collect();
+    assert_eq!(rev, vec![7, 6, 5, 4]);
+    let rev: Vec<_> = rb.iter_rev().copied().collect();
+    assert_eq!(rev, vec![7, 6, 5, 4]);
+    let newest: Vec<_> = rb.iter_rev().take(2).copied().collect();
+    assert_eq!(newest, vec![7, 6]);
+    assert_eq!(rb.iter().len(), 4);
+    assert_eq!(rb.iter_rev().len(), 4);
+
+    // meeting in the middle
+    {
+        let mut iter = rb.iter();
+        assert_eq!(iter.next(), Some(&4));
+        assert_eq!(iter.next_back(), Some(&7));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(&6));
+        assert_eq!(iter.next(), Some(&5));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    for (k, x) in rb.iter_mut().rev().enumerate() {
+        *x = k as i32;
+    }
+    assert_eq!(rb.to_vec(), vec![3, 2, 1, 0]);
+
+    let empty: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_eq!(empty.iter_rev().next(), None);
+}
+
+#[test]
+fn ring_buffer_retain_tests() {
+    // front > rear
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 6]));
+    rb.extend((0..6).map(|i| i.to_string()));
+    rb.drain_front(3).for_each(drop);
+    rb.extend((6..9).map(|i| i.to_string()));
+    assert!(rb.front() >= rb.rear());
+    assert!(rb.is_full());
+
+    rb.retain(|s| s.parse::<i32>().unwrap() % 2 == 0);
+    assert_eq!(rb.len(), 3);
+    assert_eq!(rb.to_vec(), vec!["4", "6", "8"]);
+    // removed slots don't hang on to their old values
+    let live: usize = rb.iter().map(|s| s.len()).sum();
+    let total: usize = (0..6).map(|i| rb[i].len()).sum();
+    assert_eq!(live, total);
+
+    // the ring still behaves after compaction
+    rb.enqueue_value("9".to_string());
+    assert_eq!(rb.peek_rear().map(String::as_str), Some("9"));
+    assert_eq!(rb.dequeue_value().as_deref(), Some("4"));
+    assert_eq!(rb.to_vec(), vec!["6", "8", "9"]);
+
+    rb.retain(|_| true);
+    assert_eq!(rb.len(), 3);
+    rb.retain(|_| false);
+    assert!(rb.is_empty());
+    assert_eq!(rb.iter().count(), 0);
+    assert!((0..6).all(|i| rb[i].is_empty()));
+
+    let mut empty: RingBuffer<Vec<i32>> = RingBuffer::new();
+    empty.retain(|_| false);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn ring_buffer_with_capacity_init_tests() {
+    // no Default, no Clone
+    #[derive(Debug, PartialEq)]
+    struct Handle(u32);
+
+    let mut rb = RingBuffer::<Vec<Handle>>::new().with_capacity_init(3, || Handle(!0));
+    assert_eq!(rb.capacity, 3);
+    assert!(rb.is_empty());
+    assert_eq!(rb.iter().count(), 0);
+
+    for k in 0..3 {
+        assert!(rb.enqueue_value(Handle(k)).is_some());
+    }
+    assert!(rb.enqueue_value(Handle(3)).is_none());
+    assert_eq!(rb.dequeue_replace(Handle(!0)), Some(Handle(0)));
+    rb.enqueue_value(Handle(3));
+    assert_eq!(rb.iter().map(|h| h.0).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    while rb.dequeue_replace(Handle(!0)).is_some() {}
+    assert!(rb.is_empty());
+    assert_eq!(rb.iter().count(), 0);
+    assert_eq!(rb.dequeue_replace(Handle(!0)), None);
+
+    let rb = RingBuffer::<Vec<String>>::new().with_capacity(2);
+    assert_eq!(rb.capacity, 2);
+}
+
+#[test]
+fn ring_buffer_windows_chunks_tests() {
+    let join = |(a, b): (&[i32], &[i32])| [a, b].concat();
+
+    // every rotation of a full ring and a partially full one, so some runs straddle the wrap
+    for &fill in &[7, 5] {
+        for shift in 0..7 {
+            let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 7]));
+            for _ in 0..shift {
+                rb.enqueue();
+                rb.dequeue();
+            }
+            rb.extend(0..fill);
+            let linear: Vec<i32> = rb.iter().copied().collect();
+
+            for size in 1..=8 {
+                let windows: Vec<Vec<i32>> = rb.windows(size).map(join).collect();
+                let expected: Vec<Vec<i32>> = linear.windows(size).map(|w| w.to_vec()).collect();
+                assert_eq!(windows, expected);
+
+                let chunks: Vec<Vec<i32>> = rb.chunks(size).map(join).collect();
+                let expected: Vec<Vec<i32>> = linear.chunks(size).map(|c| c.to_vec()).collect();
+                assert_eq!(chunks, expected);
+            }
+        }
+    }
+
+    let empty: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_eq!(empty.windows(1).count(), 0);
+    assert_eq!(empty.chunks(1).count(), 0);
+}
+
+#[test]
+fn ring_buffer_io_tests() {
+    use std::io::{Read, Write};
+
+    let mut rb = RingBuffer::<Vec<u8>>::new().with_capacity(7);
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let mut out = Vec::new();
+    let mut buf = [0u8; 5];
+
+    // odd sized writes/reads so the pointers wrap in every possible place
+    let mut written = 0;
+    while out.len() < data.len() {
+        let end = (written + 4).min(data.len());
+        written += rb.write(&data[written..end]).unwrap();
+        let n = rb.read(&mut buf[..3]).unwrap();
+        out.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(out, data);
+    assert!(rb.is_empty());
+    assert_eq!(rb.read(&mut buf).unwrap(), 0);
+
+    // partial write when nearly full
+    assert_eq!(rb.write(&[1, 2, 3, 4, 5]).unwrap(), 5);
+    assert_eq!(rb.write(&[6, 7, 8, 9]).unwrap(), 2);
+    assert!(rb.is_full());
+    assert_eq!(rb.write(&[10]).unwrap(), 0);
+    assert!(rb.write_all(&[10]).is_err());
+    rb.flush().unwrap();
+
+    let mut all = Vec::new();
+    rb.read_to_end(&mut all).unwrap();
+    assert_eq!(all, vec![1, 2, 3, 4, 5, 6, 7]);
+
+    let mut empty = RingBuffer::<Vec<u8>>::new();
+    assert_eq!(empty.write(&[1, 2]).unwrap(), 0);
+    assert_eq!(empty.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn ring_buffer_enqueue_slice_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty([0u32; 6]));
+    assert_eq!(rb.enqueue_slice(&[]), 0);
+    assert_eq!(rb.enqueue_slice(&[0, 1, 2, 3]), 4);
+    rb.drain_front(3).for_each(drop);
+
+    // straddles the wrap: 2 slots before the end of memory, 2 after
+    assert_eq!(rb.enqueue_slice(&[4, 5, 6, 7]), 4);
+    assert_eq!(rb.to_vec(), vec![3, 4, 5, 6, 7]);
+    assert_eq!(rb.rear(), 2);
+
+    // more than fits: only the free space gets written
+    assert_eq!(rb.enqueue_slice(&[8, 9, 10]), 1);
+    assert!(rb.is_full());
+    assert_eq!(rb.to_vec(), vec![3, 4, 5, 6, 7, 8]);
+
+    // full ring writes nothing and leaves the pointers alone
+    let (front, rear) = (rb.front(), rb.rear());
+    assert_eq!(rb.enqueue_slice(&[11]), 0);
+    assert_eq!((rb.front(), rb.rear()), (front, rear));
+    assert_eq!(rb.len(), 6);
+
+    let mut empty = RingBuffer::<Vec<u32>>::new();
+    assert_eq!(empty.enqueue_slice(&[1, 2, 3]), 0);
+}
+
+#[test]
+fn ring_buffer_dequeue_into_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0i16; 5]));
+    let mut dst = [0i16; 8];
+    assert_eq!(rb.dequeue_into(&mut dst), 0);
+
+    rb.extend(0..5);
+    rb.drain_front(3).for_each(drop);
+    rb.extend(5..8);
+    assert!(rb.is_full() && rb.front() == 3);
+
+    // across the wrap boundary
+    assert_eq!(rb.dequeue_into(&mut dst[..3]), 3);
+    assert_eq!(&dst[..3], &[3, 4, 5]);
+    assert_eq!(rb.front(), 1);
+    assert_eq!(rb.to_vec(), vec![6, 7]);
+
+    // more than is available
+    assert_eq!(rb.dequeue_into(&mut dst), 2);
+    assert_eq!(&dst[..2], &[6, 7]);
+    assert!(rb.is_empty());
+    assert_eq!(rb.dequeue_into(&mut dst), 0);
+
+    // the ring is still usable afterwards
+    assert_eq!(rb.enqueue_slice(&[8, 9, 10, 11, 12, 13]), 5);
+    assert_eq!(rb.dequeue_into(&mut dst[..0]), 0);
+    assert_eq!(rb.dequeue_into(&mut dst), 5);
+    assert_eq!(&dst[..5], &[8, 9, 10, 11, 12]);
+}
+
+#[test]
+fn ring_buffer_zero_capacity_tests() {
+    use std::io::{Read, Write};
+
+    let mut rb: RingBuffer<Vec<u8>> = RingBuffer::new();
+    let check_untouched = |rb: &RingBuffer<Vec<u8>>| {
+        assert_eq!(rb.len(), 0);
+        assert_eq!((rb.front(), rb.rear()), (0, 0));
+        assert!(rb.is_empty());
+        assert!(rb.is_full());
+    };
+    check_untouched(&rb);
+
+    assert_eq!(rb.enqueue(), None);
+    assert_eq!(rb.dequeue(), None);
+    assert_eq!(rb.pop_rear(), None);
+    assert_eq!(rb.peek_next(), None);
+    check_untouched(&rb);
+
+    assert_eq!(rb.enqueue_value(1), None);
+    assert_eq!(rb.dequeue_value(), None);
+    assert_eq!(rb.dequeue_replace(0), None);
+    assert_eq!(rb.push_overwrite(1), Some(1));
+    assert_eq!(rb.enqueue_iter(0..10), 0);
+    rb.extend(0..10);
+    assert_eq!(rb.enqueue_slice(&[1, 2]), 0);
+    assert_eq!(rb.dequeue_into(&mut [0; 2]), 0);
+    assert_eq!(rb.write(&[1, 2]).unwrap(), 0);
+    assert_eq!(rb.read(&mut [0; 2]).unwrap(), 0);
+    check_untouched(&rb);
+
+    assert_eq!(rb.peek_front(), None);
+    assert_eq!(rb.peek_front_mut(), None);
+    assert_eq!(rb.peek_rear(), None);
+    assert_eq!(rb.peek_rear_mut(), None);
+    assert_eq!(rb.get(0), None);
+    assert_eq!(rb.get_mut(0), None);
+    assert_eq!(rb.iter().count(), 0);
+    assert_eq!(rb.iter_rev().count(), 0);
+    assert_eq!(rb.iter_mut().count(), 0);
+    assert_eq!(rb.as_slices(), (&[][..], &[][..]));
+    assert_eq!(rb.as_mut_slices().0.len(), 0);
+    assert!(rb.make_contiguous().is_empty());
+    assert_eq!(rb.windows(1).count(), 0);
+    assert_eq!(rb.chunks(1).count(), 0);
+    assert_eq!(rb.drain().count(), 0);
+    assert_eq!(rb.drain_front(3).count(), 0);
+    rb.retain(|_| true);
+    rb.clear();
+    check_untouched(&rb);
+
+    assert_eq!(rb, rb.clone());
+    assert_eq!(format!("{:?}", rb), "[]");
+
+    // zero-capacity memory from the other constructors behaves the same way
+    let mut arr = RingBuffer::from_array([0u8; 0]);
+    assert_eq!(arr.enqueue(), None);
+    assert_eq!(arr.push_overwrite(3), Some(3));
+    let mut mem: [u8; 0] = [];
+    let mut slice = RingBuffer::from_slice(&mut mem[..]);
+    assert_eq!(slice.enqueue_value(3), None);
+    let mut full: RingBuffer<Vec<u8>> = RingBuffer::from(RingSpecifier::MakeFull(vec![]));
+    assert_eq!(full.dequeue(), None);
+    assert_eq!(full.pop_rear(), None);
+
+    // growing out of zero capacity works
+    rb.reserve(2);
+    assert_eq!(rb.enqueue_slice(&[1, 2]), 2);
+    assert_eq!(rb.to_vec(), vec![1, 2]);
+
+    let (mut tx, mut rx) = RingBuffer::<Vec<u8>>::new().split();
+    assert_eq!(tx.capacity(), 0);
+    assert_eq!(tx.push(1), Err(1));
+    assert_eq!(rx.pop(), None);
+    assert!(tx.is_empty() && rx.is_empty());
+}
+
+#[test]
+fn ring_buffer_rotate_tests() {
+    let collect = |rb: &RingBuffer<Vec<i32>>| rb.to_vec();
+
+    // full ring, pointers only
+    let mut rb: RingBuffer<Vec<i32>> = (0..5).collect();
+    rb.rotate_left(1);
+    assert_eq!(collect(&rb), vec![1, 2, 3, 4, 0]);
+    assert_eq!(rb.front(), 1);
+    rb.rotate_left(7);
+    assert_eq!(collect(&rb), vec![3, 4, 0, 1, 2]);
+    rb.rotate_right(3);
+    assert_eq!(collect(&rb), vec![0, 1, 2, 3, 4]);
+    rb.rotate_right(5);
+    assert_eq!(collect(&rb), vec![0, 1, 2, 3, 4]);
+
+    // partially full and wrapped, compared against VecDeque
+    for shift in 0..7 {
+        for n in 0..9 {
+            let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 7]));
+            for _ in 0..shift {
+                rb.enqueue();
+                rb.dequeue();
+            }
+            rb.extend(0..5);
+            let mut expected: std::collections::VecDeque<i32> = (0..5).collect();
+
+            rb.rotate_left(n);
+            expected.rotate_left(n % 5);
+            assert_eq!(collect(&rb), expected.iter().copied().collect::<Vec<_>>());
+            assert_eq!(rb.len(), 5);
+
+            rb.rotate_right(n + 1);
+            expected.rotate_right((n + 1) % 5);
+            assert_eq!(collect(&rb), expected.iter().copied().collect::<Vec<_>>());
+
+            // the ring still behaves afterwards
+            rb.enqueue_value(9);
+            assert_eq!(rb.peek_rear(), Some(&9));
+        }
+    }
+
+    let mut empty: RingBuffer<Vec<i32>> = RingBuffer::new();
+    empty.rotate_left(3);
+    empty.rotate_right(3);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn ring_buffer_stats_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0u8; 4]));
+    let balanced =
+        |rb: &RingBuffer<Vec<u8>>| rb.total_enqueued() - rb.total_dequeued() == rb.len() as u64;
+    assert_eq!(rb.high_watermark(), 0);
+
+    rb.enqueue_value(1);
+    rb.enqueue_value(2);
+    rb.enqueue_value(3);
+    rb.dequeue_value();
+    assert_eq!(rb.high_watermark(), 3);
+    assert_eq!((rb.total_enqueued(), rb.total_dequeued()), (3, 1));
+
+    rb.pop_rear();
+    rb.enqueue_slice(&[4, 5, 6, 7]);
+    assert_eq!(rb.high_watermark(), 4);
+    assert!(balanced(&rb));
+
+    // overwriting counts as one in and one out
+    rb.push_overwrite(8);
+    assert_eq!((rb.total_enqueued(), rb.total_dequeued()), (7, 3));
+    rb.dequeue_into(&mut [0; 2]);
+    rb.retain(|&x| x != 7);
+    rb.drain().for_each(drop);
+    assert!(balanced(&rb));
+    assert_eq!(rb.high_watermark(), 4);
+
+    rb.extend(0..3);
+    rb.clear();
+    assert!(balanced(&rb));
+
+    rb.extend(0..2);
+    rb.reset_stats();
+    assert_eq!(rb.high_watermark(), 2);
+    assert_eq!((rb.total_enqueued(), rb.total_dequeued()), (0, 0));
+    rb.enqueue();
+    assert_eq!(rb.high_watermark(), 3);
+
+    // a ring built full starts at its capacity
+    let full: RingBuffer<Vec<u8>> = (0..5).collect();
+    assert_eq!(full.high_watermark(), 5);
+    assert_eq!(full.clone().high_watermark(), 5);
+}
+
+#[test]
+fn ring_buffer_clear_drop_tests() {
+    use std::{cell::Cell, rc::Rc};
+
+    #[derive(Default)]
+    struct Counted(Option<Rc<Cell<usize>>>);
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            if let Some(drops) = &self.0 {
+                drops.set(drops.get() + 1);
+            }
+        }
+    }
+
+    let drops = Rc::new(Cell::new(0));
+    let mut rb = RingBuffer::<Vec<Counted>>::new().with_capacity(5);
+    for _ in 0..5 {
+        rb.enqueue_value(Counted(Some(drops.clone())));
+    }
+    rb.dequeue_value();
+    rb.dequeue_value();
+    rb.enqueue_value(Counted(Some(drops.clone())));
+    assert_eq!(drops.get(), 2);
+
+    // plain clear() keeps the values alive
+    let mut kept = RingBuffer::<Vec<Counted>>::new().with_capacity(2);
+    kept.enqueue_value(Counted(Some(drops.clone())));
+    kept.clear();
+    assert_eq!(drops.get(), 2);
+    assert_eq!(Rc::strong_count(&drops), 6);
+
+    // the wrapped live region gets dropped
+    rb.clear_drop();
+    assert!(rb.is_empty());
+    assert_eq!(drops.get(), 6);
+    assert_eq!(Rc::strong_count(&drops), 2);
+    assert!((0..5).all(|i| rb[i].0.is_none()));
+
+    rb.enqueue_value(Counted(Some(drops.clone())));
+    assert_eq!(rb.len(), 1);
+    rb.clear_drop();
+    assert_eq!(drops.get(), 7);
+}
+
+#[test]
+fn ring_buffer_try_enqueue_tests() {
+    // not Clone, so a lost value would be lost for good
+    #[derive(Debug, PartialEq)]
+    struct Payload(String);
+
+    let mut rb = RingBuffer::<Vec<Payload>>::new().with_capacity_init(2, || Payload(String::new()));
+    assert_eq!(rb.try_enqueue(Payload("a".into())), Ok(0));
+    assert_eq!(rb.try_enqueue(Payload("b".into())), Ok(1));
     assert_eq!(
-        rb.iter().map(|&a| a).collect::<Vec<_>>(),
-        vec![3, 4, 5, 6, 7]
+        rb.try_enqueue(Payload("c".into())),
+        Err(Payload("c".into()))
     );
+    assert_eq!(rb.len(), 2);
 
-    let popped_item = rb.pop_rear();
-    assert_eq!(rb[popped_item], 7);
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), false);
-    assert_eq!(rb.iter().map(|&a| a).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+    // retry once there's room
+    let spilled = rb.try_enqueue(Payload("d".into())).unwrap_err();
+    rb.dequeue_replace(Payload(String::new()));
+    assert_eq!(rb.try_enqueue(spilled), Ok(0));
+    let items: Vec<&str> = rb.iter().map(|p| p.0.as_str()).collect();
+    assert_eq!(items, vec!["b", "d"]);
+
+    let mut empty = RingBuffer::<Vec<u8>>::new();
+    assert_eq!(empty.try_enqueue(7), Err(7));
 }
 
 #[test]
-fn ring_buffer_enq_tests() {
-    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![1, 2, 3, 4]));
+fn ring_buffer_fill_with_tests() {
+    // not Clone
+    struct Frame(Vec<u8>);
 
-    assert_eq!(rb.is_empty(), true);
-    assert_eq!(rb.is_full(), false);
-    assert_eq!(rb.iter().map(|&a| a).collect::<Vec<_>>(), vec![]);
+    let mut rb = RingBuffer::fill_with(4, |i| Frame(vec![i as u8; 3]));
+    assert!(rb.is_full());
+    assert_eq!(rb.front(), rb.rear());
+    assert_eq!(
+        rb.iter().map(|f| f.0[0]).collect::<Vec<_>>(),
+        vec![0, 1, 2, 3]
+    );
 
-    let idx = rb.enqueue();
-    rb[idx] = -1;
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), false);
-    assert_eq!(rb.iter().map(|&a| a).collect::<Vec<_>>(), vec![-1]);
+    // refill from a wrapped, partially full state
+    rb.dequeue();
+    rb.dequeue();
+    rb.enqueue();
+    assert_ne!(rb.front(), 0);
+    rb.refill_with(|i| Frame(vec![10 + i as u8]));
+    assert!(rb.is_full());
+    assert_eq!((rb.front(), rb.rear()), (0, 0));
+    assert_eq!(
+        rb.iter().map(|f| f.0[0]).collect::<Vec<_>>(),
+        vec![10, 11, 12, 13]
+    );
+    assert_eq!(rb.capacity, 4);
 
-    let idx = rb.enqueue();
-    rb[idx] = -2;
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), false);
-    assert_eq!(rb.iter().map(|&a| a).collect::<Vec<_>>(), vec![-1, -2]);
+    let empty = RingBuffer::fill_with(0, |i| i);
+    assert!(empty.is_empty());
+    let mut arr = RingBuffer::from_array([0; 3]);
+    arr.refill_with(|i| i * i);
+    assert_eq!(arr.to_vec(), vec![0, 1, 4]);
+}
 
-    let idx = rb.enqueue();
-    rb[idx] = -3;
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), false);
-    assert_eq!(rb.iter().map(|&a| a).collect::<Vec<_>>(), vec![-1, -2, -3]);
+#[test]
+fn ring_buffer_binary_search_tests() {
+    // timestamps, wrapped so the smallest item isn't at index 0 of memory
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0u32; 8]));
+    for shift in 0..8 {
+        rb.clear();
+        for _ in 0..shift {
+            rb.enqueue();
+            rb.dequeue();
+        }
+        rb.extend(vec![10, 20, 20, 30, 40, 50, 60]);
+        let linear: Vec<u32> = rb.iter().copied().collect();
 
-    let idx = rb.enqueue();
-    rb[idx] = -4;
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), true);
+        for key in 0..70 {
+            let found = rb.binary_search_by(|t| t.cmp(&key));
+            match linear.binary_search(&key) {
+                Ok(_) => assert_eq!(rb.get(found.unwrap()), Some(&key)),
+                Err(i) => assert_eq!(found, Err(i)),
+            }
+        }
+        // not-found positions at both ends
+        assert_eq!(rb.binary_search_by(|t| t.cmp(&5)), Err(0));
+        assert_eq!(rb.binary_search_by(|t| t.cmp(&65)), Err(7));
+        assert_eq!(rb.binary_search_by(|t| t.cmp(&10)), Ok(0));
+        assert_eq!(rb.binary_search_by(|t| t.cmp(&60)), Ok(6));
+
+        // first entry newer than a timestamp
+        let newer = rb.binary_search_by(|&t| {
+            if t <= 20 {
+                cmp::Ordering::Less
+            } else {
+                cmp::Ordering::Greater
+            }
+        });
+        assert_eq!(newer, Err(3));
+        assert_eq!(rb.get(newer.unwrap_err()), Some(&30));
+    }
+
+    let empty: RingBuffer<Vec<u32>> = RingBuffer::new();
+    assert_eq!(empty.binary_search_by(|t| t.cmp(&1)), Err(0));
+}
+
+#[test]
+fn ring_buffer_enqueue_guard_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 3]));
+    rb.enqueue_value("a".to_string());
+
+    let mut guard = rb.enqueue_guard().unwrap();
+    guard.push('b');
+    assert_eq!(guard.commit(), 1);
+    assert_eq!(rb.to_vec(), vec!["a", "b"]);
+
+    // aborted guards leave the ring as it was
+    let before = rb.clone();
+    let state = |rb: &RingBuffer<Vec<String>>| {
+        (
+            rb.front(),
+            rb.rear(),
+            rb.len(),
+            rb.total_enqueued(),
+            rb.high_watermark(),
+        )
+    };
+    {
+        let mut guard = rb.enqueue_guard().unwrap();
+        guard.push_str("half written");
+    }
+    assert_eq!(rb, before);
+    assert_eq!(state(&rb), state(&before));
+    // the reserved slot is restored too, not just the logical contents
+    assert_eq!(rb[2], "");
+
+    // a failed fill, e.g. a read into the slot erroring out
+    let fill = |rb: &mut RingBuffer<Vec<String>>, ok: bool| -> Result<usize, ()> {
+        let mut guard = rb.enqueue_guard().ok_or(())?;
+        guard.clear();
+        guard.push('c');
+        if !ok {
+            return Err(());
+        }
+        Ok(guard.commit())
+    };
+    assert_eq!(fill(&mut rb, false), Err(()));
+    assert_eq!(rb, before);
+    assert_eq!(rb[2], "");
+    assert_eq!(fill(&mut rb, true), Ok(2));
+    assert_eq!(rb.peek_rear().map(String::as_str), Some("c"));
+
+    // full
+    assert!(rb.enqueue_guard().is_none());
+    assert_eq!(fill(&mut rb, true), Err(()));
+    assert!(RingBuffer::<Vec<u8>>::new().enqueue_guard().is_none());
+}
+
+#[test]
+fn ring_buffer_cursor_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 4]));
+    rb.extend(0..2);
+
+    // every cursor sees every item
+    let mut fast = rb.cursor();
+    let mut slow = rb.cursor();
+    assert_eq!(rb.read_cursor(&mut fast), Ok(Some(&0)));
+    assert_eq!(rb.read_cursor(&mut fast), Ok(Some(&1)));
+    assert_eq!(rb.read_cursor(&mut fast), Ok(None));
+    assert_eq!(rb.lag(&fast), 0);
+    assert_eq!(rb.lag(&slow), 2);
+
+    rb.enqueue_value(2);
+    assert_eq!(rb.read_cursor(&mut fast), Ok(Some(&2)));
+    assert_eq!(rb.read_cursor(&mut slow), Ok(Some(&0)));
+    assert_eq!(rb.lag(&slow), 2);
+
+    // the writer laps the slow cursor
+    for v in 3..8 {
+        rb.push_overwrite(v);
+    }
+    assert_eq!(rb.to_vec(), vec![4, 5, 6, 7]);
+    assert_eq!(rb.lag(&slow), 7);
+    assert!(rb.lag(&slow) > rb.len() as u64);
+    assert_eq!(rb.read_cursor(&mut slow), Err(Overrun { missed: 3 }));
+    assert_eq!(rb.read_cursor(&mut slow), Ok(Some(&4)));
+
+    // fast only missed 3
+    assert_eq!(rb.read_cursor(&mut fast), Err(Overrun { missed: 1 }));
+    let rest: Vec<i32> = std::iter::from_fn(|| rb.read_cursor(&mut fast).unwrap())
+        .copied()
+        .collect();
+    assert_eq!(rest, vec![4, 5, 6, 7]);
+
+    // dequeue and clear count as items leaving too
+    let mut late = rb.cursor();
+    rb.dequeue();
+    rb.dequeue_into(&mut [0; 1]);
+    assert_eq!(rb.read_cursor(&mut late), Err(Overrun { missed: 2 }));
+    rb.clear();
+    assert_eq!(rb.read_cursor(&mut late), Err(Overrun { missed: 2 }));
+    assert_eq!(rb.read_cursor(&mut late), Ok(None));
+
+    // popping the rear pulls an idle cursor back with it
+    rb.extend(10..12);
+    let mut idle = rb.cursor();
+    while let Ok(Some(_)) = rb.read_cursor(&mut idle) {}
+    rb.pop_rear();
+    assert_eq!(rb.read_cursor(&mut idle), Ok(None));
+    rb.enqueue_value(12);
+    assert_eq!(rb.read_cursor(&mut idle), Ok(Some(&12)));
+}
+
+#[test]
+fn ring_buffer_position_contains_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 5]));
+    rb.extend(0..5);
+    rb.drain_front(3).for_each(drop);
+    rb.extend(5..8);
+
+    // 6 physically lives at index 1 but logically it's the 4th item
+    assert_eq!(rb.position(|&x| x == 6), Some(3));
+    assert_eq!(rb.get(rb.position(|&x| x == 6).unwrap()), Some(&6));
+    assert_eq!(rb.position(|&x| x > 4), Some(2));
+    assert_eq!(rb.position(|&x| x == 0), None);
+
+    assert!(rb.contains(&3));
+    assert!(rb.contains(&7));
+    // dequeued items don't count
+    assert!(!rb.contains(&1));
+
+    let empty: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_eq!(empty.position(|_| true), None);
+    assert!(!empty.contains(&0));
+}
+
+#[test]
+fn ring_buffer_split_off_tests() {
+    // not Copy, and wrapped
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 6]));
+    rb.extend((0..6).map(|i| i.to_string()));
+    rb.drain_front(4).for_each(drop);
+    rb.extend((6..10).map(|i| i.to_string()));
+    assert_eq!((rb.front(), rb.len()), (4, 6));
+
+    let newer = rb.split_off(2);
+    assert_eq!(rb.to_vec(), vec!["4", "5"]);
+    assert_eq!(newer.to_vec(), vec!["6", "7", "8", "9"]);
+    assert!(newer.is_full());
+    assert_eq!(rb.len(), 2);
+    assert_eq!(rb.rear(), 0);
+    // the moved slots don't keep copies around
+    assert!((0..4).all(|i| rb[i].is_empty()));
+
+    // both halves keep working
+    rb.enqueue_value("a".to_string());
+    assert_eq!(rb.to_vec(), vec!["4", "5", "a"]);
+
+    let all = rb.split_off(0);
+    assert!(rb.is_empty());
+    assert_eq!(all.len(), 3);
+    let none = rb.split_off(0);
+    assert!(none.is_empty());
+    assert!(RingBuffer::<Vec<u8>>::new().split_off(0).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "should be <= len")]
+fn ring_buffer_split_off_out_of_bounds_test() {
+    let mut rb: RingBuffer<Vec<i32>> = (0..3).collect();
+    rb.split_off(4);
+}
+
+#[test]
+fn ring_buffer_blocking_tests() {
+    fn assert_send_sync<S: Send + Sync>() {}
+    assert_send_sync::<BlockingRing<String>>();
+
+    let ring = BlockingRing::new(2);
+    assert_eq!(ring.capacity(), 2);
+    assert_eq!(ring.try_pop(), None);
+    assert_eq!(ring.try_push(1), Ok(()));
+    assert_eq!(ring.try_push(2), Ok(()));
+    assert_eq!(ring.try_push(3), Err(3));
+    assert_eq!(ring.push_timeout(3, Duration::from_millis(10)), Err(3));
+    assert_eq!(ring.len(), 2);
+    assert_eq!(ring.pop(), 1);
+    assert_eq!(ring.pop_timeout(Duration::from_millis(10)), Some(2));
+    assert_eq!(ring.pop_timeout(Duration::from_millis(10)), None);
+    assert!(ring.is_empty());
+
+    // pop wakes up a blocked push
+    let ring = Arc::new(BlockingRing::new(1));
+    ring.push(String::from("a"));
+    let pusher = {
+        let ring = ring.clone();
+        std::thread::spawn(move || ring.push(String::from("b")))
+    };
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(ring.pop(), "a");
+    pusher.join().unwrap();
     assert_eq!(
-        rb.iter().map(|&a| a).collect::<Vec<_>>(),
-        vec![-1, -2, -3, -4]
+        ring.pop_timeout(Duration::from_secs(5)).as_deref(),
+        Some("b")
     );
+}
+
+#[test]
+fn ring_buffer_blocking_stress_test() {
+    const PRODUCERS: usize = 4;
+    const CONSUMERS: usize = 3;
+    const PER_PRODUCER: usize = 5_000;
+    let ring = Arc::new(BlockingRing::new(5));
+
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|p| {
+            let ring = ring.clone();
+            std::thread::spawn(move || {
+                for k in 0..PER_PRODUCER {
+                    ring.push(p * PER_PRODUCER + k);
+                }
+            })
+        })
+        .collect();
+
+    // consumers stop on a sentinel so nobody has to know how the items get split between them
+    let consumers: Vec<_> = (0..CONSUMERS)
+        .map(|_| {
+            let ring = ring.clone();
+            std::thread::spawn(move || {
+                let mut seen = Vec::new();
+                loop {
+                    match ring.pop() {
+                        usize::MAX => return seen,
+                        item => seen.push(item),
+                    }
+                }
+            })
+        })
+        .collect();
+
+    producers.into_iter().for_each(|p| p.join().unwrap());
+    for _ in 0..CONSUMERS {
+        ring.push(usize::MAX);
+    }
+    let mut all: Vec<usize> = consumers
+        .into_iter()
+        .flat_map(|c| c.join().unwrap())
+        .collect();
+    all.sort_unstable();
+    assert_eq!(all, (0..PRODUCERS * PER_PRODUCER).collect::<Vec<_>>());
+    assert!(ring.is_empty());
+}
+
+#[test]
+fn ring_buffer_try_get_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 2]));
+    let idx = rb.enqueue();
+    *rb.try_get_mut(idx).unwrap() = 5;
+    assert_eq!(rb.try_get(idx), Some(&5));
+    rb.enqueue();
+
+    // full, so the enqueue fails and there's nothing to write to
+    let idx = rb.enqueue();
+    assert_eq!(idx, None);
+    assert_eq!(rb.try_get(idx), None);
+    assert_eq!(rb.try_get_mut(idx), None);
+    assert_eq!(rb.try_get(Some(2)), None);
 
     let idx = rb.dequeue();
-    assert_eq!(rb[idx], -1);
-    assert_eq!(rb.is_empty(), false);
-    assert_eq!(rb.is_full(), false);
-    assert_eq!(rb.len(), 3);
-    assert_eq!(rb.iter().map(|&a| a).collect::<Vec<_>>(), vec![-2, -3, -4]);
+    assert_eq!(rb.try_get(idx), Some(&5));
+}
 
-    let front = rb.front();
-    let next_idx = rb.peek_next();
-    assert_eq!(rb[front], -2);
-    assert_eq!(rb[next_idx], -3);
+#[test]
+#[should_panic(expected = "ring was full/empty")]
+fn ring_buffer_index_none_test() {
+    let mut rb: RingBuffer<Vec<i32>> = RingBuffer::new();
+    let idx = rb.dequeue();
+    rb[idx] = 1;
+}
+
+#[test]
+#[should_panic(expected = "the capacity is 2 but the index is 3")]
+fn ring_buffer_index_out_of_bounds_test() {
+    let rb: RingBuffer<Vec<i32>> = (0..2).collect();
+    let _ = rb[Some(3)];
+}
+
+#[test]
+fn ring_buffer_contiguous_front_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0u16; 7]));
+    let mut reference = rb.clone();
+    let mut consumed = Vec::new();
+    let mut drained = Vec::new();
+
+    // zero-copy consumer taking at most 3 items per step vs a straight drain
+    for round in 0..20u16 {
+        let batch: Vec<u16> = (round * 10..round * 10 + (round % 5) + 1).collect();
+        let n = rb.enqueue_slice(&batch);
+        assert_eq!(reference.enqueue_slice(&batch[..n]), n);
+
+        let run = rb.contiguous_front();
+        assert!(!run.is_empty() || rb.is_empty());
+        let take = run.len().min(3);
+        consumed.extend_from_slice(&run[..take]);
+        rb.advance_front(take);
+        drained.extend(reference.drain_front(take));
+        assert_eq!(rb, reference);
+    }
+    consumed.extend(rb.iter().copied());
+    drained.extend(reference.drain());
+    assert_eq!(consumed, drained);
+
+    // the run stops at the wrap point
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 4]));
+    rb.extend(0..4);
+    rb.advance_front(3);
+    rb.extend(4..6);
+    assert_eq!(rb.contiguous_front(), &[3]);
+    rb.advance_front(1);
+    assert_eq!(rb.contiguous_front(), &[4, 5]);
+    rb.advance_front(0);
+    assert_eq!(rb.len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "cannot advance the front")]
+fn ring_buffer_advance_front_too_far_test() {
+    let mut rb: RingBuffer<Vec<i32>> = (0..2).collect();
+    rb.advance_front(3);
+}
+
+#[test]
+fn ring_buffer_shrink_tests() {
+    let mut rb = RingBuffer::<Vec<String>>::new().with_capacity(8);
+    rb.extend((0..8).map(|i| i.to_string()));
+    rb.drain_front(6).for_each(drop);
+    rb.extend((8..11).map(|i| i.to_string()));
+    // wrapped: 6, 7 at the end of memory and 8, 9, 10 at the start
+    assert!(rb.front() > rb.rear());
+
+    assert_eq!(rb.shrink_to(4), Err(CannotShrink));
+    assert_eq!(rb.capacity, 8);
+    assert_eq!(rb.shrink_to(6), Ok(()));
+    assert_eq!(rb.capacity, 6);
+    assert_eq!(rb.memory.len(), 6);
+    assert_eq!((rb.front(), rb.rear()), (0, 5));
+    let expected = vec!["6", "7", "8", "9", "10"];
+    assert_eq!(rb.to_vec(), expected);
+
+    // enqueue/dequeue still behave
+    rb.enqueue_value("11".to_string());
+    assert!(rb.is_full());
+    assert_eq!(rb.enqueue_value("12".to_string()), None);
+    assert_eq!(rb.dequeue_value().as_deref(), Some("6"));
+    rb.enqueue_value("12".to_string());
+    assert_eq!(rb.peek_rear().map(String::as_str), Some("12"));
+
+    // growing is not what shrink_to is for
+    assert_eq!(rb.shrink_to(20), Ok(()));
+    assert_eq!(rb.capacity, 6);
+
+    rb.drain_front(4).for_each(drop);
+    rb.shrink_to_fit();
+    assert_eq!(rb.capacity, 2);
+    assert!(rb.is_full());
+    assert_eq!(rb.to_vec(), vec!["11", "12"]);
+
+    rb.clear();
+    rb.shrink_to_fit();
+    assert_eq!(rb.capacity, 0);
+    assert_eq!(rb.enqueue_value("x".to_string()), None);
+}
+
+#[test]
+fn ring_buffer_to_vec_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 5]));
+    rb.extend((0..5).map(|i| i.to_string()));
+    rb.drain_front(3).for_each(drop);
+    rb.extend((5..7).map(|i| i.to_string()));
+    assert!(rb.front() > rb.rear());
+
+    assert_eq!(rb.to_vec(), vec!["3", "4", "5", "6"]);
+    let ptr = rb.memory.as_ptr();
+    let items = rb.into_vec();
+    assert_eq!(items, vec!["3", "4", "5", "6"]);
+    // moved, not copied into a new allocation
+    assert_eq!(items.as_ptr(), ptr);
+
+    let empty: RingBuffer<Vec<u8>> = RingBuffer::new();
+    assert!(empty.to_vec().is_empty());
+    assert!(empty.into_vec().is_empty());
+    let arr = RingBuffer::from(RingSpecifier::MakeFull([1, 2, 3]));
+    assert_eq!(arr.to_vec(), vec![1, 2, 3]);
+}
+
+#[test]
+fn ring_buffer_extend_from_slice_tests() {
+    let words: Vec<String> = ["a", "b", "c", "d", "e", "f"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 5]));
+    assert_eq!(rb.extend_from_slice(&words[..4]), 4);
+    rb.drain_front(3).for_each(drop);
+
+    // the free region wraps: one slot at the end of memory, three at the start
+    assert_eq!(rb.extend_from_slice(&words[4..]), 2);
+    assert_eq!(rb.to_vec(), vec!["d", "e", "f"]);
+    assert_eq!(rb.rear(), 1);
+
+    // more than fits
+    assert_eq!(rb.extend_from_slice(&words), 2);
+    assert_eq!(rb.to_vec(), vec!["d", "e", "f", "a", "b"]);
+
+    // full: nothing written, live items untouched
+    assert_eq!(rb.extend_from_slice(&words), 0);
+    assert_eq!(rb.to_vec(), vec!["d", "e", "f", "a", "b"]);
+    assert_eq!(rb.extend_from_slice(&[]), 0);
+}
+
+#[test]
+fn ring_buffer_monotonic_tests() {
+    // small LCG so the test doesn't need a rand dependency
+    let mut state: u32 = 0x2545_f491;
+    let mut next = move || {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (state >> 24) as i32 - 128
+    };
+
+    for &window in &[1, 2, 3, 7, 16] {
+        let mut mono = MonotonicRing::new(window);
+        let mut samples = Vec::new();
+        assert_eq!(mono.min(), None);
+        assert!(mono.is_empty());
+
+        for _ in 0..500 {
+            let v = next();
+            mono.push(v);
+            samples.push(v);
+
+            let recent = &samples[samples.len().saturating_sub(window)..];
+            assert_eq!(mono.len(), recent.len());
+            assert_eq!(mono.min(), recent.iter().min());
+            assert_eq!(mono.max(), recent.iter().max());
+        }
+    }
+
+    // plenty of ties
+    let mut mono = MonotonicRing::new(3);
+    for &v in &[5, 5, 5, 1, 5, 5, 5] {
+        mono.push(v);
+    }
+    assert_eq!((mono.min(), mono.max()), (Some(&5), Some(&5)));
+}
+
+#[test]
+fn ring_buffer_read_delayed_tests() {
+    let mut rb = RingBuffer::<Vec<f32>>::new().with_capacity(4);
+    assert_eq!(rb.read_delayed(0.0), None);
+
+    // wrap the samples across index 0: memory is [4, 5, 2, 3] with the front at 2
+    rb.extend(vec![0.0, 1.0, 2.0, 3.0]);
+    rb.push_overwrite(4.0);
+    rb.push_overwrite(5.0);
+    assert_eq!(rb.front(), 2);
+
+    assert_eq!(rb.read_delayed(0.0), Some(5.0));
+    assert_eq!(rb.read_delayed(1.0), Some(4.0));
+    assert_eq!(rb.read_delayed(3.0), Some(2.0));
+    // between memory[0] and memory[3], straddling the wrap
+    assert_eq!(rb.read_delayed(1.25), Some(3.75));
+    assert_eq!(rb.read_delayed(0.5), Some(4.5));
+    assert_eq!(rb.read_delayed(2.75), Some(2.25));
+
+    assert_eq!(rb.read_delayed(3.01), None);
+    assert_eq!(rb.read_delayed(4.0), None);
+    assert_eq!(rb.read_delayed(-0.5), None);
+    assert_eq!(rb.read_delayed(f32::NAN), None);
+
+    // integer samples with a custom interpolation
+    let mut pcm = RingBuffer::<Vec<i16>>::new().with_capacity(3);
+    pcm.extend(vec![100, 200, 400]);
+    assert_eq!(pcm.read_delayed(0.5), Some(300.0));
+    let nearest = pcm.read_delayed_with(1.37, |&a, &b, t| if t < 0.5 { a } else { b });
+    assert_eq!(nearest, Some(200));
+}
+
+#[test]
+fn ring_buffer_remove_tests() {
+    let make = || {
+        let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![String::new(); 7]));
+        rb.extend((0..7).map(|i| i.to_string()));
+        rb.drain_front(4).for_each(drop);
+        rb.extend((7..10).map(|i| i.to_string()));
+        // "4", "5", "6" at the end of memory, "7", "8", "9" at the start
+        rb
+    };
+    let strings = |v: &[i32]| v.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+
+    for i in 0..6 {
+        let mut rb = make();
+        let mut expected: Vec<i32> = (4..10).collect();
+        let removed = expected.remove(i);
+        assert_eq!(rb.remove(i), Some(removed.to_string()));
+        assert_eq!(rb.to_vec(), strings(&expected));
+        assert_eq!(rb.len(), 5);
+
+        // pointers are consistent afterwards
+        rb.enqueue_value("x".to_string());
+        assert_eq!(rb.peek_rear().map(String::as_str), Some("x"));
+        assert_eq!(rb.dequeue_value(), Some(expected[0].to_string()));
+    }
+
+    let mut rb = make();
+    assert_eq!(rb.remove(6), None);
+    assert_eq!(rb.remove(0).as_deref(), Some("4"));
+    assert_eq!(rb.remove(4).as_deref(), Some("9"));
+    assert_eq!(rb.to_vec(), vec!["5", "6", "7", "8"]);
+    while rb.remove(0).is_some() {}
+    assert!(rb.is_empty());
+    // nothing stale is left behind
+    assert!((0..7).all(|i| rb[i].is_empty()));
+    assert_eq!(RingBuffer::<Vec<u8>>::new().remove(0), None);
+}
+
+#[test]
+fn ring_buffer_iter_mut_tests() {
+    let mut rb = RingBuffer::from(RingSpecifier::MakeEmpty(vec![0; 5]));
+    rb.extend(0..5);
+    rb.drain_front(3).for_each(drop);
+    rb.extend(5..7);
+
+    // hold on to every reference at once, they all have to be disjoint
+    let refs: Vec<&mut i32> = rb.iter_mut().collect();
+    assert_eq!(refs.len(), 4);
+    for r in refs {
+        *r *= 10;
+    }
+    assert_eq!(rb.to_vec(), vec![30, 40, 50, 60]);
+
+    let mut iter = rb.iter_mut();
+    assert_eq!(iter.len(), 4);
+    *iter.next_back().unwrap() += 1;
+    *iter.next().unwrap() += 2;
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.rev().map(|x| *x).collect::<Vec<_>>(), vec![50, 40]);
+    assert_eq!(rb.to_vec(), vec![32, 40, 50, 61]);
+
+    let mut empty: RingBuffer<Vec<i32>> = RingBuffer::new();
+    assert_eq!(empty.iter_mut().next(), None);
+}
+
+#[test]
+#[should_panic]
+fn ring_buffer_windows_zero_size_test() {
+    let rb: RingBuffer<Vec<i32>> = (0..4).collect();
+    rb.windows(0);
 }